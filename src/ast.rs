@@ -1,12 +1,104 @@
 //Created by: Nisha Murali (Requested not to clone / re-use the code)
 //asst.rs (Structure and enums:Token, Statement, Expression, etc.)
-use crate::tokenizer::Keyword;
+//! The syntax tree produced by the parser.
+//!
+//! With the `serde` feature every type here can be serialized. The JSON uses
+//! serde's default enum encoding: a variant without data is a string
+//! (`"NotNull"`), any other variant is an object whose only key is the variant
+//! name (`{"Identifier": "age"}`). Struct fields keep their Rust names and
+//! spans are `{"start": {"offset", "line", "column"}, "end": {...}}`.
+//!
+//! Spans are left out when nodes are compared, so two ASTs parsed from
+//! differently formatted text are equal as long as their structure matches.
+//! Compare the `span` fields directly when the position itself matters.
+use crate::span::Span;
+
+/// An expression together with the source text it was parsed from.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Expression 
+{
+    pub kind: ExpressionKind,
+    pub span: Span,
+}
+
+impl Expression 
+{
+    pub fn new(kind: ExpressionKind, span: Span) -> Self 
+    {
+        Self { kind, span }
+    }
+
+    /// The CASE expression that `NULLIF(a, b)` or `COALESCE(a, b, ...)`
+    /// is shorthand for, or `None` for any other expression.
+    ///
+    /// `NULLIF(a, b)` is `CASE WHEN a = b THEN NULL ELSE a END` and
+    /// `COALESCE(a, b, c)` is
+    /// `CASE WHEN a IS NOT NULL THEN a WHEN b IS NOT NULL THEN b ELSE c END`.
+    pub fn to_case(&self) -> Option<Expression> 
+    {
+        let ExpressionKind::Function { name, args, .. } = &self.kind else 
+        {
+            return None;
+        };
+        let args = args.iter()
+            .map(|arg| match arg 
+            {
+                FunctionArg::Expression(expr) => Some(expr.clone()),
+                FunctionArg::Wildcard => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let node = |kind| Expression::new(kind, self.span);
+
+        let (branches, else_result) = match (name.to_uppercase().as_str(), args.as_slice()) 
+        {
+            ("NULLIF", [a, b]) => 
+            {
+                let condition = node(ExpressionKind::BinaryOperation 
+                {
+                    left_operand: Box::new(a.clone()),
+                    operator: BinaryOperator::Equals,
+                    right_operand: Box::new(b.clone()),
+                });
+                (vec![CaseBranch { condition, result: node(ExpressionKind::Null) }], Some(a.clone()))
+            }
+            ("COALESCE", [rest @ .., last]) => 
+            {
+                // A single argument needs a branch of its own: CASE has at
+                // least one WHEN.
+                let (tested, else_result) = if rest.is_empty() { (args.as_slice(), None) } else { (rest, Some(last.clone())) };
+                let branches = tested.iter()
+                    .map(|arg| CaseBranch 
+                    {
+                        condition: node(ExpressionKind::IsNull { expr: Box::new(arg.clone()), negated: true }),
+                        result: arg.clone(),
+                    })
+                    .collect();
+                (branches, else_result)
+            }
+            _ => return None,
+        };
+        Some(node(ExpressionKind::Case { operand: None, branches, else_result: else_result.map(Box::new) }))
+    }
+}
+
+impl PartialEq for Expression 
+{
+    fn eq(&self, other: &Self) -> bool 
+    {
+        self.kind == other.kind
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum Expression 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExpressionKind 
 {
     Identifier(String),
-    Number(u64),
+    /// A qualified column reference such as `users.name`.
+    CompoundIdentifier(Vec<String>),
+    /// A numeric literal kept as written, see `Token::Number`.
+    Number(String),
     String(String),
     UnaryOperation 
     {
@@ -21,10 +113,98 @@ pub enum Expression
     },
     Boolean(bool),
     Null,
+    /// A bind parameter, see `Token::Placeholder`.
+    Placeholder(String),
     Grouped(Box<Expression>),
+    /// A query used as a value, `(SELECT MAX(x) FROM t)`.
+    Subquery(Box<Query>),
+    /// `EXISTS (SELECT ...)`
+    Exists(Box<Query>),
+    /// `expr [NOT] IN (a, b, ...)`
+    InList 
+    {
+        expr: Box<Expression>,
+        list: Vec<Expression>,
+        negated: bool,
+    },
+    /// `expr [NOT] IN (SELECT ...)`
+    InSubquery 
+    {
+        expr: Box<Expression>,
+        subquery: Box<Query>,
+        negated: bool,
+    },
+    /// A comparison against every row of a subquery, `x > ALL (SELECT ...)`.
+    QuantifiedComparison 
+    {
+        left_operand: Box<Expression>,
+        operator: BinaryOperator,
+        quantifier: Quantifier,
+        subquery: Box<Query>,
+    },
+    /// `expr IS [NOT] NULL`
+    IsNull 
+    {
+        expr: Box<Expression>,
+        negated: bool,
+    },
+    /// `CASE [operand] WHEN ... THEN ... [ELSE ...] END`. With an operand
+    /// each WHEN value is compared to it; without one each WHEN is a
+    /// condition.
+    Case 
+    {
+        operand: Option<Box<Expression>>,
+        branches: Vec<CaseBranch>,
+        else_result: Option<Box<Expression>>,
+    },
+    /// A function call such as `LOWER(name)` or `COUNT(DISTINCT id)`.
+    /// Functions the registry knows are named in upper case, others as
+    /// written.
+    Function 
+    {
+        name: String,
+        args: Vec<FunctionArg>,
+        distinct: bool,
+        /// `FILTER (WHERE ...)` after an aggregate.
+        filter: Option<Box<Expression>>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Quantifier 
+{
+    Any,
+    All,
+}
+
+/// `WHEN condition THEN result` inside a CASE.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaseBranch 
+{
+    pub condition: Expression,
+    pub result: Expression,
+}
+
+/// Binding power of `[NOT] IN`, the same as `<` and `>`.
+pub const IN_PRECEDENCE: u8 = 6;
+
+/// Binding power of `IS [NOT] NULL`, below every comparison and above NOT:
+/// `a = b IS NULL` is `(a = b) IS NULL`.
+pub const IS_PRECEDENCE: u8 = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FunctionArg 
+{
+    /// `*`, as in `COUNT(*)`
+    Wildcard,
+    Expression(Expression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator 
 {
     Equals,
@@ -41,39 +221,412 @@ pub enum BinaryOperator
     Divide,
 }
 
+impl BinaryOperator 
+{
+    /// `=`, `!=`, `<`, `<=`, `>` or `>=`, the operators ANY and ALL can follow.
+    pub fn is_comparison(&self) -> bool 
+    {
+        matches!(self, BinaryOperator::Equals | BinaryOperator::NotEquals |
+            BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual |
+            BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual)
+    }
+
+    /// Binding power used by the Pratt parser; higher binds tighter. All
+    /// binary operators are left-associative.
+    pub fn precedence(&self) -> u8 
+    {
+        match self 
+        {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 2,
+            BinaryOperator::Equals | BinaryOperator::NotEquals => 5,
+            BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual |
+            BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual => 6,
+            BinaryOperator::Add | BinaryOperator::Subtract => 7,
+            BinaryOperator::Multiply | BinaryOperator::Divide => 8,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOperator 
 {
     Not,
     Negate,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl UnaryOperator 
+{
+    /// Minimum precedence of the operand: `NOT` takes a whole comparison or
+    /// IS test, unary minus only a product.
+    pub fn precedence(&self) -> u8 
+    {
+        match self 
+        {
+            UnaryOperator::Not => 3,
+            UnaryOperator::Negate => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::large_enum_variant)]
 pub enum Statement 
 {
-    Select 
+    /// A SELECT or a compound query such as `... UNION ...`.
+    Query(Box<Query>),
+    CreateTable 
     {
+        table_name: String,
+        columns: Vec<TableColumn>,
+        span: Span,
+    },
+    Insert 
+    {
+        with: Option<With>,
+        table_name: String,
         columns: Vec<String>,
-        table: String,
+        source: InsertSource,
+        span: Span,
+    },
+    Update 
+    {
+        with: Option<With>,
+        table_name: String,
+        assignments: Vec<Assignment>,
         selection: Option<Expression>,
-        order_by: Option<Vec<String>>,
+        span: Span,
     },
-    CreateTable 
+    Delete 
     {
+        with: Option<With>,
         table_name: String,
-        columns: Vec<TableColumn>,
+        selection: Option<Expression>,
+        span: Span,
     },
 }
 
+impl Statement 
+{
+    pub fn span(&self) -> Span 
+    {
+        match self 
+        {
+            Statement::Query(query) => query.span,
+            Statement::CreateTable { span, .. } => *span,
+            Statement::Insert { span, .. } => *span,
+            Statement::Update { span, .. } => *span,
+            Statement::Delete { span, .. } => *span,
+        }
+    }
+}
+
+impl PartialEq for Statement 
+{
+    fn eq(&self, other: &Self) -> bool 
+    {
+        match (self, other) 
+        {
+            (Statement::Query(a), Statement::Query(b)) => a == b,
+            (
+                Statement::CreateTable { table_name: a_name, columns: a_columns, span: _ },
+                Statement::CreateTable { table_name: b_name, columns: b_columns, span: _ },
+            ) => a_name == b_name && a_columns == b_columns,
+            (
+                Statement::Insert { with: a_with, table_name: a_name, columns: a_columns, source: a_source, span: _ },
+                Statement::Insert { with: b_with, table_name: b_name, columns: b_columns, source: b_source, span: _ },
+            ) => a_with == b_with && a_name == b_name && a_columns == b_columns && a_source == b_source,
+            (
+                Statement::Update { with: a_with, table_name: a_name, assignments: a_assignments, selection: a_selection, span: _ },
+                Statement::Update { with: b_with, table_name: b_name, assignments: b_assignments, selection: b_selection, span: _ },
+            ) => a_with == b_with && a_name == b_name && a_assignments == b_assignments && a_selection == b_selection,
+            (
+                Statement::Delete { with: a_with, table_name: a_name, selection: a_selection, span: _ },
+                Statement::Delete { with: b_with, table_name: b_name, selection: b_selection, span: _ },
+            ) => a_with == b_with && a_name == b_name && a_selection == b_selection,
+            _ => false,
+        }
+    }
+}
+
+/// A complete query: an optional WITH clause, a body that may combine
+/// several SELECTs, and the ORDER BY and row limits that apply to the whole
+/// result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Query 
+{
+    pub with: Option<With>,
+    pub body: SetExpr,
+    pub order_by: Vec<OrderByItem>,
+    /// `LIMIT n`, or the count of MySQL's `LIMIT offset, count`.
+    pub limit: Option<Expression>,
+    /// `OFFSET m`, with or without `ROWS`.
+    pub offset: Option<Expression>,
+    /// `FETCH FIRST n ROWS ONLY`. `FETCH FIRST ROW ONLY` is stored as 1.
+    pub fetch: Option<Expression>,
+    pub span: Span,
+}
+
+impl PartialEq for Query 
+{
+    fn eq(&self, other: &Self) -> bool 
+    {
+        let Query { with, body, order_by, limit, offset, fetch, span: _ } = self;
+        *with == other.with && *body == other.body && *order_by == other.order_by
+            && *limit == other.limit && *offset == other.offset && *fetch == other.fetch
+    }
+}
+
+/// The body of a query, a tree of SELECTs joined by set operators.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetExpr 
+{
+    Select(Box<Select>),
+    /// A parenthesized query, which may carry its own ORDER BY and limits.
+    Query(Box<Query>),
+    /// `left UNION [ALL | DISTINCT] right`, and likewise INTERSECT and EXCEPT.
+    SetOperation 
+    {
+        left: Box<SetExpr>,
+        operator: SetOperator,
+        quantifier: Option<SetQuantifier>,
+        right: Box<SetExpr>,
+    },
+}
+
+impl SetExpr 
+{
+    /// The select list of the leftmost SELECT, which names the columns of
+    /// the whole result.
+    pub fn columns(&self) -> &[SelectItem] 
+    {
+        match self 
+        {
+            SetExpr::Select(select) => &select.columns,
+            SetExpr::Query(query) => query.body.columns(),
+            SetExpr::SetOperation { left, .. } => left.columns(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetOperator 
+{
+    Union,
+    Intersect,
+    Except,
+}
+
+impl SetOperator 
+{
+    /// INTERSECT binds tighter than UNION and EXCEPT.
+    pub fn precedence(&self) -> u8 
+    {
+        match self 
+        {
+            SetOperator::Union | SetOperator::Except => 1,
+            SetOperator::Intersect => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetQuantifier 
+{
+    All,
+    Distinct,
+}
+
+/// A single `SELECT ... FROM ... WHERE ... GROUP BY ... HAVING ...` block.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Select 
+{
+    /// `TOP n` right after SELECT.
+    pub top: Option<Expression>,
+    pub columns: Vec<SelectItem>,
+    pub from: Vec<TableReference>,
+    pub selection: Option<Expression>,
+    pub group_by: Vec<Expression>,
+    pub having: Option<Expression>,
+    pub span: Span,
+}
+
+impl PartialEq for Select 
+{
+    fn eq(&self, other: &Self) -> bool 
+    {
+        let Select { top, columns, from, selection, group_by, having, span: _ } = self;
+        *top == other.top && *columns == other.columns && *from == other.from
+            && *selection == other.selection && *group_by == other.group_by && *having == other.having
+    }
+}
+
+/// `WITH [RECURSIVE] cte, ...` in front of a statement.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct With 
+{
+    pub recursive: bool,
+    pub ctes: Vec<Cte>,
+}
+
+/// One common table expression, `name [(columns)] AS [[NOT] MATERIALIZED] (query)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cte 
+{
+    pub name: String,
+    pub columns: Vec<String>,
+    pub materialized: Option<CteMaterialization>,
+    pub query: Box<Query>,
+}
+
+/// PostgreSQL's hint on whether a CTE is computed once or inlined.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CteMaterialization 
+{
+    Materialized,
+    NotMaterialized,
+}
+
+/// Where the rows of an INSERT come from.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InsertSource 
+{
+    Values(Vec<Vec<Expression>>),
+    Query(Box<Query>),
+}
+
+/// `column = value` in an UPDATE's SET list.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Assignment 
+{
+    pub column: String,
+    pub value: Expression,
+}
+
+/// One entry in a SELECT projection list.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelectItem 
+{
+    /// `*`
+    Wildcard,
+    /// `table.*`
+    QualifiedWildcard(String),
+    /// `expr`, `expr AS alias` or `expr alias`
+    Expression 
+    {
+        expr: Expression,
+        alias: Option<String>,
+    },
+}
+
+/// One entry of an ORDER BY list: an expression, or a 1-based position in
+/// the select list such as `2`, with optional `ASC`/`DESC` and
+/// `NULLS FIRST`/`NULLS LAST`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderByItem 
+{
+    pub expr: Expression,
+    pub direction: Option<OrderDirection>,
+    pub nulls: Option<NullsOrder>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrderDirection 
+{
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NullsOrder 
+{
+    First,
+    Last,
+}
+
+/// One comma-separated entry of a FROM clause, with any joins hanging off it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableReference 
+{
+    Table 
+    {
+        name: String,
+        alias: Option<String>,
+    },
+    /// A subquery in FROM, `(SELECT ...) AS t`.
+    Derived 
+    {
+        subquery: Box<Query>,
+        alias: Option<String>,
+    },
+    Join 
+    {
+        left: Box<TableReference>,
+        operator: JoinOperator,
+        right: Box<TableReference>,
+        constraint: JoinConstraint,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinOperator 
+{
+    Inner,
+    LeftOuter,
+    RightOuter,
+    FullOuter,
+    Cross,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinConstraint 
+{
+    On(Expression),
+    Using(Vec<String>),
+    Natural,
+    None,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableColumn 
 {
     pub name: String,
     pub data_type: DBType,
     pub constraints: Vec<Constraint>,
+    pub span: Span,
+}
+
+impl PartialEq for TableColumn 
+{
+    fn eq(&self, other: &Self) -> bool 
+    {
+        let TableColumn { name, data_type, constraints, span: _ } = self;
+        *name == other.name && *data_type == other.data_type && *constraints == other.constraints
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DBType 
 {
     Int,
@@ -82,6 +635,7 @@ pub enum DBType
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Constraint 
 {
     NotNull,
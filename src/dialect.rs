@@ -0,0 +1,433 @@
+//Created by: Nisha Murali (Requested not to clone / re-use the code)
+//dialect.rs (SQL dialects the tokenizer and parser follow)
+use crate::tokenizer::Keyword;
+
+/// The parts of the grammar that differ from one database to another. The
+/// tokenizer asks it how words and quotes are read and the parser asks it
+/// which statements are allowed. Every method has a default, so a custom
+/// dialect only overrides what it changes.
+pub trait Dialect: std::fmt::Debug
+{
+    /// Name used in error messages, e.g. "PostgreSQL".
+    fn name(&self) -> &'static str;
+
+    /// If `ch` opens a quoted identifier, the character that closes it.
+    fn identifier_quote_end(&self, ch: char) -> Option<char>
+    {
+        (ch == '"').then_some('"')
+    }
+
+    /// The character identifiers are quoted with when SQL is printed for
+    /// this dialect.
+    fn identifier_quote(&self) -> char
+    {
+        '"'
+    }
+
+    /// Whether `ch` opens (and closes) a string literal.
+    fn is_string_quote(&self, ch: char) -> bool
+    {
+        ch == '\''
+    }
+
+    /// Whether backslash escapes such as `\n` work inside ordinary strings.
+    fn supports_backslash_escapes(&self) -> bool
+    {
+        false
+    }
+
+    /// Whether `E'...'` strings with backslash escapes are recognised.
+    fn supports_escape_string_literals(&self) -> bool
+    {
+        false
+    }
+
+    /// Whether `keyword` is reserved. Unreserved keywords are read as plain
+    /// identifiers, so they can name columns and tables.
+    fn is_reserved_keyword(&self, _keyword: &Keyword) -> bool
+    {
+        true
+    }
+
+    /// Whether statements starting with `keyword` (SELECT, INSERT, ...) are
+    /// allowed.
+    fn supports_statement(&self, _keyword: &Keyword) -> bool
+    {
+        true
+    }
+
+    /// Whether the symbolic operator spelled `operator` (e.g. `!=`) is allowed.
+    fn supports_operator(&self, _operator: &str) -> bool
+    {
+        true
+    }
+
+    /// Whether `LIMIT count` is accepted.
+    fn supports_limit(&self) -> bool
+    {
+        true
+    }
+
+    /// Whether `LIMIT offset, count` is accepted as well as `LIMIT count`.
+    fn supports_limit_comma(&self) -> bool
+    {
+        false
+    }
+
+    /// Whether `FETCH FIRST n ROWS ONLY` is accepted.
+    fn supports_fetch(&self) -> bool
+    {
+        true
+    }
+
+    /// Whether `SELECT TOP n ...` is accepted.
+    fn supports_top(&self) -> bool
+    {
+        false
+    }
+}
+
+/// The dialect used when none is given: `"` identifiers, `E'...'` strings,
+/// every operator and every way of limiting rows.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GenericDialect;
+
+impl Dialect for GenericDialect
+{
+    fn name(&self) -> &'static str
+    {
+        "generic"
+    }
+
+    fn supports_escape_string_literals(&self) -> bool
+    {
+        true
+    }
+
+    fn supports_limit_comma(&self) -> bool
+    {
+        true
+    }
+
+    fn supports_top(&self) -> bool
+    {
+        true
+    }
+}
+
+/// Standard SQL: `"` identifiers, `<>` but not `!=`, every keyword reserved
+/// and `FETCH FIRST` rather than `LIMIT`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AnsiDialect;
+
+impl Dialect for AnsiDialect
+{
+    fn name(&self) -> &'static str
+    {
+        "ANSI"
+    }
+
+    fn supports_operator(&self, operator: &str) -> bool
+    {
+        operator != "!="
+    }
+
+    fn supports_limit(&self) -> bool
+    {
+        false
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PostgreSqlDialect;
+
+impl Dialect for PostgreSqlDialect
+{
+    fn name(&self) -> &'static str
+    {
+        "PostgreSQL"
+    }
+
+    fn supports_escape_string_literals(&self) -> bool
+    {
+        true
+    }
+}
+
+/// MySQL: `` ` `` identifiers, `'` or `"` strings with backslash escapes and
+/// `LIMIT offset, count`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MySqlDialect;
+
+impl Dialect for MySqlDialect
+{
+    fn name(&self) -> &'static str
+    {
+        "MySQL"
+    }
+
+    fn identifier_quote_end(&self, ch: char) -> Option<char>
+    {
+        (ch == '`').then_some('`')
+    }
+
+    fn identifier_quote(&self) -> char
+    {
+        '`'
+    }
+
+    fn is_string_quote(&self, ch: char) -> bool
+    {
+        ch == '\'' || ch == '"'
+    }
+
+    fn supports_backslash_escapes(&self) -> bool
+    {
+        true
+    }
+
+    /// LEFT and RIGHT are also string functions and KEY is a common column
+    /// name; the parser still reads them in joins and PRIMARY KEY.
+    fn is_reserved_keyword(&self, keyword: &Keyword) -> bool
+    {
+        !matches!(keyword, Keyword::Left | Keyword::Right | Keyword::Key)
+    }
+
+    fn supports_limit_comma(&self) -> bool
+    {
+        true
+    }
+
+    fn supports_fetch(&self) -> bool
+    {
+        false
+    }
+}
+
+/// SQLite: identifiers in `"`, `` ` `` or `[...]` and `LIMIT offset, count`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SQLiteDialect;
+
+impl Dialect for SQLiteDialect
+{
+    fn name(&self) -> &'static str
+    {
+        "SQLite"
+    }
+
+    fn identifier_quote_end(&self, ch: char) -> Option<char>
+    {
+        match ch
+        {
+            '"' => Some('"'),
+            '`' => Some('`'),
+            '[' => Some(']'),
+            _ => None,
+        }
+    }
+
+    fn supports_limit_comma(&self) -> bool
+    {
+        true
+    }
+
+    fn supports_fetch(&self) -> bool
+    {
+        false
+    }
+}
+
+/// SQL Server: identifiers in `"` or `[...]`, `TOP n` and `FETCH` but no
+/// `LIMIT`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MsSqlDialect;
+
+impl Dialect for MsSqlDialect
+{
+    fn name(&self) -> &'static str
+    {
+        "SQL Server"
+    }
+
+    fn identifier_quote_end(&self, ch: char) -> Option<char>
+    {
+        match ch
+        {
+            '"' => Some('"'),
+            '[' => Some(']'),
+            _ => None,
+        }
+    }
+
+    fn supports_limit(&self) -> bool
+    {
+        false
+    }
+
+    fn supports_top(&self) -> bool
+    {
+        true
+    }
+}
+
+/// Looks up a built-in dialect by name, ignoring case: `generic`, `ansi`,
+/// `postgres` (or `postgresql`), `mysql`, `sqlite` or `mssql`.
+pub fn dialect_from_name(name: &str) -> Option<Box<dyn Dialect>>
+{
+    match name.to_lowercase().as_str()
+    {
+        "generic" => Some(Box::new(GenericDialect)),
+        "ansi" => Some(Box::new(AnsiDialect)),
+        "postgres" | "postgresql" => Some(Box::new(PostgreSqlDialect)),
+        "mysql" => Some(Box::new(MySqlDialect)),
+        "sqlite" => Some(Box::new(SQLiteDialect)),
+        "mssql" => Some(Box::new(MsSqlDialect)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Statement, SelectItem, ExpressionKind, SetExpr};
+    use crate::error::ParseErrorKind;
+    use crate::parse_sql_with_dialect;
+
+    fn first_column(statement: &Statement) -> &ExpressionKind {
+        match statement {
+            Statement::Query(query) => match &query.body {
+                SetExpr::Select(select) => match &select.columns[0] {
+                    SelectItem::Expression { expr, .. } => &expr.kind,
+                    other => panic!("unexpected select item {:?}", other),
+                },
+                other => panic!("expected a single SELECT, got {:?}", other),
+            },
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_mysql_backticks_and_backslashes() {
+        let statements = parse_sql_with_dialect(r#"SELECT `order` FROM t WHERE a = "it\'s""#, &MySqlDialect).unwrap();
+        assert_eq!(first_column(&statements[0]), &ExpressionKind::Identifier("order".to_string()));
+        assert_eq!(statements[0].display(&MySqlDialect).to_string(), "SELECT `order` FROM t WHERE a = 'it''s'");
+        assert_eq!(statements[0].to_string(), "SELECT \"order\" FROM t WHERE a = 'it''s'");
+
+        // A backslash is doubled so the string reads back the same.
+        let statements = parse_sql_with_dialect(r"SELECT 'x\\y', 'tab\there' FROM t", &MySqlDialect).unwrap();
+        let printed = statements[0].display(&MySqlDialect).to_string();
+        assert_eq!(printed, "SELECT 'x\\\\y', 'tab\there' FROM t");
+        assert_eq!(parse_sql_with_dialect(&printed, &MySqlDialect).unwrap(), statements);
+
+        // Backticks mean nothing to PostgreSQL.
+        let err = parse_sql_with_dialect("SELECT `order` FROM t", &PostgreSqlDialect).unwrap_err();
+        assert_eq!(err.span.start.column, 8);
+    }
+
+    #[test]
+    fn test_mysql_left_right_and_key() {
+        let sql = "SELECT LEFT(name, 2), RIGHT(name, 1), key FROM t LEFT JOIN u ON t.key = u.key RIGHT OUTER JOIN v USING (key)";
+        let statements = parse_sql_with_dialect(sql, &MySqlDialect).unwrap();
+        let printed = statements[0].display(&MySqlDialect).to_string();
+        assert_eq!(printed, "SELECT LEFT(name, 2), RIGHT(name, 1), key FROM t LEFT JOIN u ON t.key = u.key RIGHT JOIN v USING (key)");
+        assert_eq!(parse_sql_with_dialect(&printed, &MySqlDialect).unwrap(), statements);
+
+        assert!(parse_sql_with_dialect("CREATE TABLE t (key INT PRIMARY KEY)", &MySqlDialect).is_ok());
+        assert!(parse_sql_with_dialect("SELECT key FROM t", &PostgreSqlDialect).is_err());
+    }
+
+    #[test]
+    fn test_mysql_quoted_keywords_as_implicit_aliases() {
+        let sql = "SELECT a `left`, b `select` FROM t `right` LEFT JOIN u `key` ON TRUE, v `left`";
+        let statements = parse_sql_with_dialect(sql, &MySqlDialect).unwrap();
+        let printed = statements[0].display(&MySqlDialect).to_string();
+        assert_eq!(printed, "SELECT a AS left, b AS `select` FROM t AS right LEFT JOIN u AS key ON TRUE, v AS left");
+        assert_eq!(parse_sql_with_dialect(&printed, &MySqlDialect).unwrap(), statements);
+
+        let statements = parse_sql_with_dialect("SELECT * FROM t `left`", &MySqlDialect).unwrap();
+        assert_eq!(statements[0].display(&MySqlDialect).to_string(), "SELECT * FROM t AS left");
+    }
+
+    #[test]
+    fn test_aggregate_names_are_not_keywords() {
+        let statements = parse_sql_with_dialect("SELECT count FROM t", &PostgreSqlDialect).unwrap();
+        assert_eq!(first_column(&statements[0]), &ExpressionKind::Identifier("count".to_string()));
+
+        let statements = parse_sql_with_dialect("SELECT count(*) FROM t", &PostgreSqlDialect).unwrap();
+        assert_eq!(statements[0].to_string(), "SELECT COUNT(*) FROM t");
+
+        assert!(parse_sql_with_dialect("SELECT count, max FROM t", &AnsiDialect).is_ok());
+    }
+
+    #[test]
+    fn test_ansi_rejects_bang_equals() {
+        let err = parse_sql_with_dialect("SELECT * FROM t WHERE a != 1", &AnsiDialect).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidToken("Operator != is not supported by the ANSI dialect".to_string()));
+        assert_eq!(err.span.start.column, 25);
+        let statements = parse_sql_with_dialect("SELECT * FROM t WHERE a <> 1", &AnsiDialect).unwrap();
+        // Both spellings print as the standard one, so ANSI reads the output back.
+        let printed = statements[0].display(&AnsiDialect).to_string();
+        assert_eq!(printed, "SELECT * FROM t WHERE a <> 1");
+        assert_eq!(parse_sql_with_dialect("SELECT * FROM t WHERE a != 1", &GenericDialect).unwrap(), statements);
+        assert_eq!(parse_sql_with_dialect(&printed, &AnsiDialect).unwrap(), statements);
+    }
+
+    #[test]
+    fn test_sqlite_bracket_identifiers() {
+        let statements = parse_sql_with_dialect("SELECT [first name] FROM [people]", &SQLiteDialect).unwrap();
+        assert_eq!(statements[0].to_string(), "SELECT \"first name\" FROM people");
+    }
+
+    #[test]
+    fn test_row_limit_variants() {
+        let err = parse_sql_with_dialect("SELECT * FROM t LIMIT 5", &AnsiDialect).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("LIMIT is not supported by the ANSI dialect".to_string()));
+        assert!(parse_sql_with_dialect("SELECT * FROM t OFFSET 5 ROWS FETCH FIRST 5 ROWS ONLY", &AnsiDialect).is_ok());
+
+        assert!(parse_sql_with_dialect("SELECT * FROM t LIMIT 5, 10", &MySqlDialect).is_ok());
+        let err = parse_sql_with_dialect("SELECT * FROM t LIMIT 5, 10", &PostgreSqlDialect).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("LIMIT offset, count is not supported by the PostgreSQL dialect".to_string()));
+        assert_eq!(err.span.start.column, 24);
+        assert!(parse_sql_with_dialect("SELECT * FROM t FETCH FIRST 5 ROWS ONLY", &SQLiteDialect).is_err());
+
+        let statements = parse_sql_with_dialect("SELECT TOP 5 [name] FROM t", &MsSqlDialect).unwrap();
+        assert_eq!(statements[0].to_string(), "SELECT TOP 5 name FROM t");
+        let err = parse_sql_with_dialect("SELECT TOP 5 name FROM t", &PostgreSqlDialect).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("TOP is not supported by the PostgreSQL dialect".to_string()));
+        // Without a count TOP is an ordinary column name.
+        assert!(parse_sql_with_dialect("SELECT top FROM t", &PostgreSqlDialect).is_ok());
+        assert!(parse_sql_with_dialect("SELECT top FROM t", &MsSqlDialect).is_ok());
+        let statements = parse_sql_with_dialect("SELECT top(1) FROM t", &PostgreSqlDialect).unwrap();
+        assert!(matches!(first_column(&statements[0]), ExpressionKind::Function { name, .. } if name == "top"));
+        let statements = parse_sql_with_dialect("SELECT TOP (1) name FROM t", &MsSqlDialect).unwrap();
+        assert_eq!(statements[0].to_string(), "SELECT TOP (1) name FROM t");
+    }
+
+    #[derive(Debug)]
+    struct ReadOnly;
+
+    impl Dialect for ReadOnly {
+        fn name(&self) -> &'static str {
+            "read-only"
+        }
+
+        fn supports_statement(&self, keyword: &Keyword) -> bool {
+            *keyword == Keyword::Select
+        }
+    }
+
+    #[test]
+    fn test_custom_dialect_limits_statements() {
+        assert!(parse_sql_with_dialect("SELECT * FROM t", &ReadOnly).is_ok());
+        let err = parse_sql_with_dialect("SELECT * FROM t; DELETE FROM t", &ReadOnly).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("DELETE statements are not supported by the read-only dialect".to_string()));
+        assert_eq!(err.span.start.column, 18);
+    }
+
+    #[test]
+    fn test_dialect_from_name() {
+        assert_eq!(dialect_from_name("PostgreSQL").unwrap().name(), "PostgreSQL");
+        assert_eq!(dialect_from_name("mysql").unwrap().name(), "MySQL");
+        assert!(dialect_from_name("oracle").is_none());
+    }
+}
@@ -0,0 +1,820 @@
+//Created by: Nisha Murali (Requested not to clone / re-use the code)
+//display.rs (Prints the AST back out as SQL)
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::ast::{
+    Statement, Expression, ExpressionKind, FunctionArg, BinaryOperator, UnaryOperator, SelectItem,
+    TableReference, JoinOperator, JoinConstraint, InsertSource, Assignment, TableColumn, DBType, Constraint,
+    OrderByItem, OrderDirection, NullsOrder, Quantifier, IN_PRECEDENCE, IS_PRECEDENCE, With, Cte, CteMaterialization,
+    Query, Select, SetExpr, SetOperator, SetQuantifier,
+};
+use crate::dialect::Dialect;
+use crate::tokenizer::{Tokenizer, Keyword};
+
+/// What printing needs to know about the dialect the SQL is for.
+struct Quoting
+{
+    /// The character `Ident` quotes with.
+    quote: char,
+    /// Whether strings need their backslashes doubled to read back.
+    backslash_escapes: bool,
+    /// Keywords `Ident` may print bare.
+    unreserved: Vec<Keyword>,
+}
+
+thread_local!
+{
+    /// `DialectDisplay` swaps in the dialect's quoting while it prints.
+    static QUOTING: RefCell<Quoting> = const
+    {
+        RefCell::new(Quoting { quote: '"', backslash_escapes: false, unreserved: Vec::new() })
+    };
+}
+
+/// An identifier, quoted when it would not otherwise read back as the same
+/// identifier (keywords, spaces, leading digits, ...).
+struct Ident<'a>(&'a str);
+
+impl fmt::Display for Ident<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        QUOTING.with_borrow(|Quoting { quote, unreserved, .. }|
+        {
+            let mut chars = self.0.chars();
+            let plain = chars.next().is_some_and(|c| c.is_alphabetic())
+                && chars.all(|c| c.is_alphanumeric() || c == '_')
+                && Tokenizer::lookup_keyword(self.0).is_none_or(|k| k.is_contextual() || unreserved.contains(&k));
+            if plain
+            {
+                write!(f, "{}", self.0)
+            }
+            else
+            {
+                write!(f, "{}{}{}", quote, self.0.replace(*quote, &format!("{}{}", quote, quote)), quote)
+            }
+        })
+    }
+}
+
+/// A statement printed for a particular dialect, see [`Statement::display`].
+pub struct DialectDisplay<'a>
+{
+    statement: &'a Statement,
+    dialect: &'a dyn Dialect,
+}
+
+impl Statement
+{
+    /// Prints the statement with the identifier quotes `dialect` reads, e.g.
+    /// `` `order` `` for MySQL, leaving the keywords it does not reserve
+    /// bare. `to_string` always uses double quotes.
+    pub fn display<'a>(&'a self, dialect: &'a dyn Dialect) -> DialectDisplay<'a>
+    {
+        DialectDisplay { statement: self, dialect }
+    }
+}
+
+impl fmt::Display for DialectDisplay<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let quoting = Quoting
+        {
+            quote: self.dialect.identifier_quote(),
+            backslash_escapes: self.dialect.supports_backslash_escapes(),
+            unreserved: Tokenizer::keywords().filter(|k| !self.dialect.is_reserved_keyword(k)).collect(),
+        };
+        let previous = QUOTING.replace(quoting);
+        let result = write!(f, "{}", self.statement);
+        QUOTING.set(previous);
+        result
+    }
+}
+
+fn write_idents(f: &mut fmt::Formatter<'_>, names: &[String]) -> fmt::Result
+{
+    let idents: Vec<Ident> = names.iter().map(|name| Ident(name)).collect();
+    write_list(f, &idents)
+}
+
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result
+{
+    for (i, item) in items.iter().enumerate()
+    {
+        if i > 0
+        {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+/// Writes `expr` as an operand, parenthesised only if it would otherwise
+/// bind less tightly than `min_precedence` when parsed back.
+fn write_operand(f: &mut fmt::Formatter<'_>, expr: &Expression, min_precedence: u8, left_edge: bool) -> fmt::Result
+{
+    let needs_parens = match &expr.kind
+    {
+        ExpressionKind::BinaryOperation { operator, .. } => operator.precedence() < min_precedence,
+        // A prefix operator on the left would swallow the operator after it.
+        ExpressionKind::UnaryOperation { operator, .. } => left_edge && min_precedence >= operator.precedence(),
+        ExpressionKind::InList { .. } | ExpressionKind::InSubquery { .. } => IN_PRECEDENCE < min_precedence,
+        ExpressionKind::IsNull { .. } => IS_PRECEDENCE < min_precedence,
+        ExpressionKind::QuantifiedComparison { operator, .. } => operator.precedence() < min_precedence,
+        _ => false,
+    };
+    if needs_parens
+    {
+        write!(f, "({})", expr)
+    }
+    else
+    {
+        write!(f, "{}", expr)
+    }
+}
+
+impl fmt::Display for Expression
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl fmt::Display for ExpressionKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            ExpressionKind::Identifier(name) => write!(f, "{}", Ident(name)),
+            ExpressionKind::CompoundIdentifier(parts) =>
+            {
+                for (i, part) in parts.iter().enumerate()
+                {
+                    if i > 0
+                    {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}", Ident(part))?;
+                }
+                Ok(())
+            }
+            ExpressionKind::Number(n) => write!(f, "{}", n),
+            ExpressionKind::String(s) =>
+            {
+                let mut escaped = s.replace('\'', "''");
+                if QUOTING.with_borrow(|quoting| quoting.backslash_escapes)
+                {
+                    escaped = escaped.replace('\\', "\\\\");
+                }
+                write!(f, "'{}'", escaped)
+            }
+            ExpressionKind::Boolean(true) => write!(f, "TRUE"),
+            ExpressionKind::Boolean(false) => write!(f, "FALSE"),
+            ExpressionKind::Null => write!(f, "NULL"),
+            ExpressionKind::Placeholder(p) => write!(f, "{}", p),
+            ExpressionKind::Grouped(expr) => write!(f, "({})", expr),
+            ExpressionKind::Subquery(subquery) => write!(f, "({})", subquery),
+            ExpressionKind::Exists(subquery) => write!(f, "EXISTS ({})", subquery),
+            ExpressionKind::InList { expr, list, negated } =>
+            {
+                write_operand(f, expr, IN_PRECEDENCE, true)?;
+                write!(f, " {}IN (", if *negated { "NOT " } else { "" })?;
+                write_list(f, list)?;
+                write!(f, ")")
+            }
+            ExpressionKind::InSubquery { expr, subquery, negated } =>
+            {
+                write_operand(f, expr, IN_PRECEDENCE, true)?;
+                write!(f, " {}IN ({})", if *negated { "NOT " } else { "" }, subquery)
+            }
+            ExpressionKind::IsNull { expr, negated } =>
+            {
+                write_operand(f, expr, IS_PRECEDENCE, true)?;
+                write!(f, " IS {}NULL", if *negated { "NOT " } else { "" })
+            }
+            ExpressionKind::Case { operand, branches, else_result } =>
+            {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand
+                {
+                    write!(f, " {}", operand)?;
+                }
+                for branch in branches
+                {
+                    write!(f, " WHEN {} THEN {}", branch.condition, branch.result)?;
+                }
+                if let Some(else_result) = else_result
+                {
+                    write!(f, " ELSE {}", else_result)?;
+                }
+                write!(f, " END")
+            }
+            ExpressionKind::QuantifiedComparison { left_operand, operator, quantifier, subquery } =>
+            {
+                write_operand(f, left_operand, operator.precedence(), true)?;
+                write!(f, " {} {} ({})", operator, quantifier, subquery)
+            }
+            ExpressionKind::UnaryOperation { operator, operand } =>
+            {
+                write!(f, "{}", operator)?;
+                // Keep `- -x` from printing as a `--` comment.
+                if let ExpressionKind::UnaryOperation { operator: UnaryOperator::Negate, .. } = operand.kind
+                {
+                    write!(f, " ")?;
+                }
+                write_operand(f, operand, operator.precedence(), false)
+            }
+            ExpressionKind::BinaryOperation { left_operand, operator, right_operand } =>
+            {
+                write_operand(f, left_operand, operator.precedence(), true)?;
+                write!(f, " {} ", operator)?;
+                write_operand(f, right_operand, operator.precedence() + 1, false)
+            }
+            ExpressionKind::Function { name, args, distinct, filter } =>
+            {
+                // Qualified names such as schema.func are kept as one string.
+                for (i, part) in name.split('.').enumerate()
+                {
+                    if i > 0
+                    {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}", Ident(part))?;
+                }
+                write!(f, "(")?;
+                if *distinct
+                {
+                    write!(f, "DISTINCT ")?;
+                }
+                write_list(f, args)?;
+                write!(f, ")")?;
+                if let Some(filter) = filter
+                {
+                    write!(f, " FILTER (WHERE {})", filter)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Quantifier
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            Quantifier::Any => write!(f, "ANY"),
+            Quantifier::All => write!(f, "ALL"),
+        }
+    }
+}
+
+impl fmt::Display for FunctionArg
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            FunctionArg::Wildcard => write!(f, "*"),
+            FunctionArg::Expression(expr) => write!(f, "{}", expr),
+        }
+    }
+}
+
+impl fmt::Display for BinaryOperator
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let symbol = match self
+        {
+            BinaryOperator::Equals => "=",
+            BinaryOperator::NotEquals => "<>",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::GreaterThanOrEqual => ">=",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::LessThanOrEqual => "<=",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Or => "OR",
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+impl fmt::Display for UnaryOperator
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            UnaryOperator::Not => write!(f, "NOT "),
+            UnaryOperator::Negate => write!(f, "-"),
+        }
+    }
+}
+
+impl fmt::Display for SelectItem
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SelectItem::Wildcard => write!(f, "*"),
+            SelectItem::QualifiedWildcard(table) => write!(f, "{}.*", Ident(table)),
+            SelectItem::Expression { expr, alias: Some(alias) } => write!(f, "{} AS {}", expr, Ident(alias)),
+            SelectItem::Expression { expr, alias: None } => write!(f, "{}", expr),
+        }
+    }
+}
+
+impl fmt::Display for TableReference
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            TableReference::Table { name, alias: Some(alias) } => write!(f, "{} AS {}", Ident(name), Ident(alias)),
+            TableReference::Table { name, alias: None } => write!(f, "{}", Ident(name)),
+            TableReference::Derived { subquery, alias: Some(alias) } => write!(f, "({}) AS {}", subquery, Ident(alias)),
+            TableReference::Derived { subquery, alias: None } => write!(f, "({})", subquery),
+            TableReference::Join { left, operator, right, constraint } =>
+            {
+                write!(f, "{} ", left)?;
+                if *constraint == JoinConstraint::Natural
+                {
+                    write!(f, "NATURAL ")?;
+                }
+                write!(f, "{} {}", operator, right)?;
+                match constraint
+                {
+                    JoinConstraint::On(expr) => write!(f, " ON {}", expr),
+                    JoinConstraint::Using(columns) =>
+                    {
+                        write!(f, " USING (")?;
+                        write_idents(f, columns)?;
+                        write!(f, ")")
+                    }
+                    JoinConstraint::Natural | JoinConstraint::None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for JoinOperator
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            JoinOperator::Inner => write!(f, "JOIN"),
+            JoinOperator::LeftOuter => write!(f, "LEFT JOIN"),
+            JoinOperator::RightOuter => write!(f, "RIGHT JOIN"),
+            JoinOperator::FullOuter => write!(f, "FULL JOIN"),
+            JoinOperator::Cross => write!(f, "CROSS JOIN"),
+        }
+    }
+}
+
+impl fmt::Display for Assignment
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{} = {}", Ident(&self.column), self.value)
+    }
+}
+
+impl fmt::Display for DBType
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            DBType::Int => write!(f, "INT"),
+            DBType::Bool => write!(f, "BOOL"),
+            DBType::Varchar => write!(f, "VARCHAR"),
+        }
+    }
+}
+
+impl fmt::Display for Constraint
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            Constraint::NotNull => write!(f, "NOT NULL"),
+            Constraint::PrimaryKey => write!(f, "PRIMARY KEY"),
+            Constraint::Check(expr) => write!(f, "CHECK ({})", expr),
+        }
+    }
+}
+
+impl fmt::Display for TableColumn
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{} {}", Ident(&self.name), self.data_type)?;
+        for constraint in &self.constraints
+        {
+            write!(f, " {}", constraint)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for OrderByItem
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", self.expr)?;
+        match self.direction
+        {
+            Some(OrderDirection::Asc) => write!(f, " ASC")?,
+            Some(OrderDirection::Desc) => write!(f, " DESC")?,
+            None => {}
+        }
+        match self.nulls
+        {
+            Some(NullsOrder::First) => write!(f, " NULLS FIRST"),
+            Some(NullsOrder::Last) => write!(f, " NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for With
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "WITH ")?;
+        if self.recursive
+        {
+            write!(f, "RECURSIVE ")?;
+        }
+        write_list(f, &self.ctes)
+    }
+}
+
+impl fmt::Display for Cte
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", Ident(&self.name))?;
+        if !self.columns.is_empty()
+        {
+            write!(f, " (")?;
+            write_idents(f, &self.columns)?;
+            write!(f, ")")?;
+        }
+        write!(f, " AS ")?;
+        match self.materialized
+        {
+            Some(CteMaterialization::Materialized) => write!(f, "MATERIALIZED ")?,
+            Some(CteMaterialization::NotMaterialized) => write!(f, "NOT MATERIALIZED ")?,
+            None => {}
+        }
+        write!(f, "({})", self.query)
+    }
+}
+
+impl fmt::Display for Query
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if let Some(with) = &self.with
+        {
+            write!(f, "{} ", with)?;
+        }
+        write!(f, "{}", self.body)?;
+        if !self.order_by.is_empty()
+        {
+            write!(f, " ORDER BY ")?;
+            write_list(f, &self.order_by)?;
+        }
+        if let Some(limit) = &self.limit
+        {
+            write!(f, " LIMIT {}", limit)?;
+        }
+        if let Some(offset) = &self.offset
+        {
+            write!(f, " OFFSET {}", offset)?;
+            if self.fetch.is_some()
+            {
+                write!(f, " ROWS")?;
+            }
+        }
+        if let Some(fetch) = &self.fetch
+        {
+            write!(f, " FETCH FIRST {} ROWS ONLY", fetch)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes one side of a set operation, in parentheses when it is itself a
+/// set operation that would otherwise group differently.
+fn write_set_operand(f: &mut fmt::Formatter<'_>, operand: &SetExpr, min_precedence: u8) -> fmt::Result
+{
+    match operand
+    {
+        SetExpr::SetOperation { operator, .. } if operator.precedence() < min_precedence => write!(f, "({})", operand),
+        _ => write!(f, "{}", operand),
+    }
+}
+
+impl fmt::Display for SetExpr
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SetExpr::Select(select) => write!(f, "{}", select),
+            SetExpr::Query(query) => write!(f, "({})", query),
+            SetExpr::SetOperation { left, operator, quantifier, right } =>
+            {
+                write_set_operand(f, left, operator.precedence())?;
+                write!(f, " {} ", operator)?;
+                match quantifier
+                {
+                    Some(SetQuantifier::All) => write!(f, "ALL ")?,
+                    Some(SetQuantifier::Distinct) => write!(f, "DISTINCT ")?,
+                    None => {}
+                }
+                write_set_operand(f, right, operator.precedence() + 1)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SetOperator
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+impl fmt::Display for Select
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "SELECT ")?;
+        if let Some(top) = &self.top
+        {
+            write!(f, "TOP {} ", top)?;
+        }
+        write_list(f, &self.columns)?;
+        write!(f, " FROM ")?;
+        write_list(f, &self.from)?;
+        if let Some(selection) = &self.selection
+        {
+            write!(f, " WHERE {}", selection)?;
+        }
+        if !self.group_by.is_empty()
+        {
+            write!(f, " GROUP BY ")?;
+            write_list(f, &self.group_by)?;
+        }
+        if let Some(having) = &self.having
+        {
+            write!(f, " HAVING {}", having)?;
+        }
+        Ok(())
+    }
+}
+
+/// Statements print on a single line without a trailing `;`.
+impl fmt::Display for Statement
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if let Statement::Insert { with: Some(with), .. }
+            | Statement::Update { with: Some(with), .. }
+            | Statement::Delete { with: Some(with), .. } = self
+        {
+            write!(f, "{} ", with)?;
+        }
+        match self
+        {
+            Statement::Query(query) => write!(f, "{}", query),
+            Statement::CreateTable { table_name, columns, .. } =>
+            {
+                write!(f, "CREATE TABLE {} (", Ident(table_name))?;
+                write_list(f, columns)?;
+                write!(f, ")")
+            }
+            Statement::Insert { table_name, columns, source, .. } =>
+            {
+                write!(f, "INSERT INTO {}", Ident(table_name))?;
+                if !columns.is_empty()
+                {
+                    write!(f, " (")?;
+                    write_idents(f, columns)?;
+                    write!(f, ")")?;
+                }
+                match source
+                {
+                    InsertSource::Values(rows) =>
+                    {
+                        write!(f, " VALUES ")?;
+                        for (i, row) in rows.iter().enumerate()
+                        {
+                            if i > 0
+                            {
+                                write!(f, ", ")?;
+                            }
+                            write!(f, "(")?;
+                            write_list(f, row)?;
+                            write!(f, ")")?;
+                        }
+                        Ok(())
+                    }
+                    InsertSource::Query(query) => write!(f, " {}", query),
+                }
+            }
+            Statement::Update { table_name, assignments, selection, .. } =>
+            {
+                write!(f, "UPDATE {} SET ", Ident(table_name))?;
+                write_list(f, assignments)?;
+                if let Some(selection) = selection
+                {
+                    write!(f, " WHERE {}", selection)?;
+                }
+                Ok(())
+            }
+            Statement::Delete { table_name, selection, .. } =>
+            {
+                write!(f, "DELETE FROM {}", Ident(table_name))?;
+                if let Some(selection) = selection
+                {
+                    write!(f, " WHERE {}", selection)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::Span;
+    use crate::test_helpers::{parse_statement, ident, binary};
+    use crate::dialect::{GenericDialect, AnsiDialect, PostgreSqlDialect, MySqlDialect, SQLiteDialect, MsSqlDialect};
+    use crate::parse_sql_with_dialect;
+
+    fn assert_round_trip(input: &str, expected: &str) {
+        let ast = parse_statement(input).unwrap();
+        let printed = ast.to_string();
+        assert_eq!(printed, expected);
+        assert_eq!(parse_statement(&printed).unwrap(), ast);
+    }
+
+    #[test]
+    fn test_round_trip_in_every_dialect() {
+        let shared = [
+            (
+                "select 'it''s', a <> b, (a is null) = b, (a in (1)) + 1, a = b is null, a in (1) = b not in (2) from t",
+                "SELECT 'it''s', a <> b, (a IS NULL) = b, (a IN (1)) + 1, a = b IS NULL, a IN (1) = b NOT IN (2) FROM t",
+            ),
+            (
+                r#"select "nulls", "select" "first", "Mixed Case" from t "order" order by "nulls" nulls last"#,
+                r#"SELECT nulls, "select" AS first, "Mixed Case" FROM t AS "order" ORDER BY nulls NULLS LAST"#,
+            ),
+        ];
+        let mysql = [
+            (
+                r"select 'x\\y', 'it''s', a <> b, (a is null) = b from t",
+                r"SELECT 'x\\y', 'it''s', a <> b, (a IS NULL) = b FROM t",
+            ),
+            (
+                "select `nulls`, `select` `first`, `key` from t `left` left join u `right` on true",
+                "SELECT nulls, `select` AS first, key FROM t AS left LEFT JOIN u AS right ON TRUE",
+            ),
+        ];
+        let dialects: [&dyn Dialect; 5] = [&GenericDialect, &AnsiDialect, &PostgreSqlDialect, &SQLiteDialect, &MsSqlDialect];
+        let cases = dialects.iter()
+            .flat_map(|dialect| shared.iter().map(move |(input, expected)| (*dialect, *input, *expected)))
+            .chain(mysql.iter().map(|(input, expected)| (&MySqlDialect as &dyn Dialect, *input, *expected)));
+        for (dialect, input, expected) in cases {
+            let statements = parse_sql_with_dialect(input, dialect).unwrap_or_else(|e| panic!("{:?} rejects {}: {:?}", dialect, input, e));
+            let printed = statements[0].display(dialect).to_string();
+            assert_eq!(printed, expected, "printed by {:?}", dialect);
+            assert_eq!(parse_sql_with_dialect(&printed, dialect).unwrap(), statements, "reparsed by {:?}", dialect);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_statements() {
+        assert_round_trip(
+            "select u.*, price * qty total, COUNT(DISTINCT id) from orders o left outer join users u on o.uid = u.id \
+             where not a = 1 and (b or c) group by region having count(*) > 2 order by total desc, 1 asc nulls first;",
+            "SELECT u.*, price * qty AS total, COUNT(DISTINCT id) FROM orders AS o LEFT JOIN users AS u ON o.uid = u.id \
+             WHERE NOT a = 1 AND (b OR c) GROUP BY region HAVING COUNT(*) > 2 ORDER BY total DESC, 1 ASC NULLS FIRST",
+        );
+        assert_round_trip(
+            "CREATE TABLE students (id INT PRIMARY KEY, name VARCHAR NOT NULL, age INT CHECK(age >= 18))",
+            "CREATE TABLE students (id INT PRIMARY KEY, name VARCHAR NOT NULL, age INT CHECK (age >= 18))",
+        );
+        assert_round_trip(
+            "INSERT INTO t (a, b) VALUES (1, -2.50), (.5e-9, 'x')",
+            "INSERT INTO t (a, b) VALUES (1, -2.50), (.5e-9, 'x')",
+        );
+        assert_round_trip("INSERT INTO t SELECT * FROM s", "INSERT INTO t SELECT * FROM s");
+        assert_round_trip("UPDATE t SET a = a - (1 - b), c = NULL WHERE TRUE", "UPDATE t SET a = a - (1 - b), c = NULL WHERE TRUE");
+        assert_round_trip("DELETE FROM t", "DELETE FROM t");
+        assert_round_trip("SELECT * FROM t LIMIT 10 OFFSET ?", "SELECT * FROM t LIMIT 10 OFFSET ?");
+        assert_round_trip(
+            "select * from (select a from t) x where (a in (1, 2)) = true and b not in (select b from u) \
+             and not exists (select 1 from v) and c >= any (select c from w) and (select max(d) from w) > 0",
+            "SELECT * FROM (SELECT a FROM t) AS x WHERE (a IN (1, 2)) = TRUE AND b NOT IN (SELECT b FROM u) \
+             AND NOT EXISTS (SELECT 1 FROM v) AND c >= ANY (SELECT c FROM w) AND (SELECT MAX(d) FROM w) > 0",
+        );
+        assert_round_trip(
+            "with recursive r (n) as (select 1 from t), s as not materialized (select n from r) delete from t where id in (select n from s)",
+            "WITH RECURSIVE r (n) AS (SELECT 1 FROM t), s AS NOT MATERIALIZED (SELECT n FROM r) DELETE FROM t WHERE id IN (SELECT n FROM s)",
+        );
+        assert_round_trip(
+            "select a from t union all (select a from u intersect select a from v) except (select a from w limit 1) order by 1",
+            "SELECT a FROM t UNION ALL (SELECT a FROM u INTERSECT SELECT a FROM v) EXCEPT (SELECT a FROM w LIMIT 1) ORDER BY 1",
+        );
+        assert_round_trip(
+            "select date_trunc('month', created), count(*) filter (where paid), my_fn() from t group by 1",
+            "SELECT DATE_TRUNC('month', created), COUNT(*) FILTER (WHERE paid), my_fn() FROM t GROUP BY 1",
+        );
+        assert_round_trip(
+            r#"select "My Func"(x), app."left"(y), "select"() from t"#,
+            r#"SELECT "My Func"(x), app."left"(y), "select"() FROM t"#,
+        );
+        assert_round_trip(
+            "select case when score >= 90 then 'A' when score is null then '-' else 'B' end grade, case kind when 1 then 'x' end from t where (a is null) = false",
+            "SELECT CASE WHEN score >= 90 THEN 'A' WHEN score IS NULL THEN '-' ELSE 'B' END AS grade, CASE kind WHEN 1 THEN 'x' END FROM t WHERE (a IS NULL) = FALSE",
+        );
+        assert_round_trip(
+            "select a = b is null, a = (b is null), a < b is not null, (a is null) = b, a is null and b from t",
+            "SELECT a = b IS NULL, a = (b IS NULL), a < b IS NOT NULL, (a IS NULL) = b, a IS NULL AND b FROM t",
+        );
+        assert_round_trip(
+            "select (a in (1)) + 1, a in (1) = b not in (select c from u), a < b in (1), a in (1) is null from t",
+            "SELECT (a IN (1)) + 1, a IN (1) = b NOT IN (SELECT c FROM u), a < b IN (1), a IN (1) IS NULL FROM t",
+        );
+        assert_round_trip("SELECT * FROM t LIMIT 20, 10", "SELECT * FROM t LIMIT 10 OFFSET 20");
+        assert_round_trip(
+            "SELECT TOP (:n) * FROM t OFFSET $1 FETCH NEXT ROW ONLY",
+            "SELECT TOP (:n) * FROM t OFFSET $1 ROWS FETCH FIRST 1 ROWS ONLY",
+        );
+        assert_round_trip(
+            r#"SELECT "Order Id", "select".x AS "2nd" FROM "My Table" WHERE note = 'O''Brien' OR note = E'a\'b'"#,
+            r#"SELECT "Order Id", "select".x AS "2nd" FROM "My Table" WHERE note = 'O''Brien' OR note = 'a''b'"#,
+        );
+        assert_round_trip("SELECT * FROM a NATURAL JOIN b, c CROSS JOIN d JOIN e USING (x)", "SELECT * FROM a NATURAL JOIN b, c CROSS JOIN d JOIN e USING (x)");
+    }
+
+    #[test]
+    fn test_minimal_parentheses_from_precedence() {
+        let sum = binary(ident("a"), BinaryOperator::Add, ident("b"));
+        assert_eq!(binary(sum.clone(), BinaryOperator::Multiply, ident("c")).to_string(), "(a + b) * c");
+        assert_eq!(binary(ident("c"), BinaryOperator::Subtract, sum.clone()).to_string(), "c - (a + b)");
+        assert_eq!(binary(sum.clone(), BinaryOperator::Subtract, ident("c")).to_string(), "a + b - c");
+        let product = binary(ident("a"), BinaryOperator::Multiply, ident("b"));
+        assert_eq!(binary(ident("c"), BinaryOperator::Add, product).to_string(), "c + a * b");
+
+        let not_a = Expression::new(ExpressionKind::UnaryOperation {
+            operator: UnaryOperator::Not,
+            operand: Box::new(ident("a")),
+        }, Span::default());
+        assert_eq!(binary(not_a.clone(), BinaryOperator::Equals, ident("b")).to_string(), "(NOT a) = b");
+        assert_eq!(binary(not_a, BinaryOperator::And, ident("b")).to_string(), "NOT a AND b");
+
+        let body = |sql: &str| match parse_statement(sql).unwrap() {
+            Statement::Query(query) => query.body,
+            other => panic!("expected a query, got {:?}", other),
+        };
+        let set = |left, operator, right| SetExpr::SetOperation {
+            left: Box::new(left),
+            operator,
+            quantifier: None,
+            right: Box::new(right),
+        };
+        let union = set(body("SELECT a FROM t"), SetOperator::Union, body("SELECT a FROM u"));
+        assert_eq!(set(union.clone(), SetOperator::Intersect, body("SELECT a FROM v")).to_string(),
+            "(SELECT a FROM t UNION SELECT a FROM u) INTERSECT SELECT a FROM v");
+        assert_eq!(set(body("SELECT a FROM v"), SetOperator::Except, union).to_string(),
+            "SELECT a FROM v EXCEPT (SELECT a FROM t UNION SELECT a FROM u)");
+    }
+}
@@ -1,30 +1,180 @@
 //Created by: Nisha Murali (Requested not to clone / re-use the code)
 // error.rs (Handles all the parsing errors)
-#[derive(Debug)]
-pub enum ParseError 
+use crate::span::Span;
+use crate::tokenizer::SpannedToken;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind
 {
     UnexpectedEnd,
     ExpectedKeyword(String),
     ExpectedIdentifier,
     InvalidExpression(String),
     UnknownStartOfStatement(String),
+    /// Text the tokenizer could not turn into a token.
+    InvalidToken(String),
     General(String),
 }
 
-impl std::fmt::Display for ParseError 
+/// A parse failure, where it happened and what was found there instead.
+/// The notes are boxed to keep `Result<_, ParseError>` small.
+#[derive(Debug, Clone)]
+pub struct ParseError
+{
+    pub kind: ParseErrorKind,
+    pub span: Span,
+    pub found: Option<Box<str>>,
+    pub help: Option<Box<str>>,
+}
+
+impl ParseError
+{
+    pub fn new(kind: ParseErrorKind, span: Span) -> Self
+    {
+        Self { kind, span, found: None, help: None }
+    }
+
+    /// An error pointing at `token`, recording it as what was found.
+    pub fn at(kind: ParseErrorKind, token: &SpannedToken) -> Self
+    {
+        Self::new(kind, token.span).with_found(token.token.to_string())
+    }
+
+    pub fn with_found(mut self, found: impl Into<String>) -> Self
+    {
+        self.found = Some(found.into().into_boxed_str());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self
+    {
+        self.help = Some(help.into().into_boxed_str());
+        self
+    }
+
+    /// Renders the error against the text it was parsed from, rustc style:
+    ///
+    /// ```text
+    /// error: Expected keyword: FROM
+    ///  --> line 1, column 13
+    ///   |
+    /// 1 | SELECT a, b FORM users
+    ///   |             ^^^^ found `FORM`
+    ///   = help: ...
+    /// ```
+    pub fn render(&self, source: &str) -> String
+    {
+        let line_no = self.span.start.line;
+        let column = self.span.start.column;
+        let line = source.lines().nth(line_no.saturating_sub(1)).unwrap_or("");
+        let gutter = " ".repeat(line_no.to_string().len());
+
+        let line_len = line.chars().count();
+        let underline_end = if self.span.end.line == line_no
+        {
+            self.span.end.column.min(line_len + 1)
+        }
+        else
+        {
+            line_len + 1
+        };
+        let width = underline_end.saturating_sub(column).max(1);
+
+        let mut out = format!("error: {}\n", self.kind);
+        out.push_str(&format!("{} --> line {}, column {}\n", gutter, line_no, column));
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!("{} | {}\n", line_no, line));
+        out.push_str(&format!("{} | {}{}", gutter, " ".repeat(column.saturating_sub(1)), "^".repeat(width)));
+        if let Some(found) = &self.found
+        {
+            out.push_str(&format!(" found `{}`", found));
+        }
+        out.push('\n');
+        if let Some(help) = &self.help
+        {
+            out.push_str(&format!("{} = help: {}\n", gutter, help));
+        }
+        out
+    }
+}
+
+impl std::fmt::Display for ParseErrorKind
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
     {
-        match self 
+        match self
         {
-            ParseError::UnexpectedEnd => write!(f, "Unexpected end of input, Please check once again"),
-            ParseError::ExpectedKeyword(k) => write!(f, "Expected keyword: {}", k),
-            ParseError::ExpectedIdentifier => write!(f, "Expected an identifier, Check once again"),
-            ParseError::InvalidExpression(e) => write!(f, "Invalid expression: {}", e),
-            ParseError::UnknownStartOfStatement(t) => write!(f, "Unknown start of statement: {}", t),
-            ParseError::General(e) => write!(f, "{}", e),
+            ParseErrorKind::UnexpectedEnd => write!(f, "Unexpected end of input, Please check once again"),
+            ParseErrorKind::ExpectedKeyword(k) => write!(f, "Expected keyword: {}", k),
+            ParseErrorKind::ExpectedIdentifier => write!(f, "Expected an identifier, Check once again"),
+            ParseErrorKind::InvalidExpression(e) => write!(f, "Invalid expression: {}", e),
+            ParseErrorKind::UnknownStartOfStatement(t) => write!(f, "Unknown start of statement: {}", t),
+            ParseErrorKind::InvalidToken(e) => write!(f, "{}", e),
+            ParseErrorKind::General(e) => write!(f, "{}", e),
         }
     }
 }
 
+impl std::fmt::Display for ParseError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "{} (line {}, column {})", self.kind, self.span.start.line, self.span.start.column)
+    }
+}
+
 impl std::error::Error for ParseError {}
+
+/// A statement in a script that failed to parse. `index` counts statements
+/// from 0 and `span` covers the whole statement up to its `;`.
+#[derive(Debug, Clone)]
+pub struct StatementError
+{
+    pub index: usize,
+    pub span: Span,
+    pub error: ParseError,
+}
+
+impl StatementError
+{
+    pub fn render(&self, source: &str) -> String
+    {
+        format!("in statement {}:\n{}", self.index + 1, self.error.render(source))
+    }
+}
+
+impl std::fmt::Display for StatementError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "statement {}: {}", self.index + 1, self.error)
+    }
+}
+
+impl std::error::Error for StatementError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::Location;
+
+    #[test]
+    fn test_render_points_at_span() {
+        let source = "SELECT a\nFROM users WHER x";
+        let span = Span::new(
+            Location { offset: 20, line: 2, column: 12 },
+            Location { offset: 24, line: 2, column: 16 },
+        );
+        let err = ParseError::new(ParseErrorKind::General("Unexpected token".to_string()), span)
+            .with_found("WHER")
+            .with_help("did you mean WHERE?");
+
+        let expected = "error: Unexpected token\n  \
+                        --> line 2, column 12\n  \
+                        |\n\
+                        2 | FROM users WHER x\n  \
+                        |            ^^^^ found `WHER`\n  \
+                        = help: did you mean WHERE?\n";
+        assert_eq!(err.render(source), expected);
+    }
+}
@@ -0,0 +1,148 @@
+//Created by: Nisha Murali (Requested not to clone / re-use the code)
+//functions.rs (Known functions and how many arguments they take)
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionKind
+{
+    Scalar,
+    /// Aggregates also accept `DISTINCT` and `FILTER (WHERE ...)`.
+    Aggregate,
+}
+
+/// What the parser knows about one function: its name as printed, whether
+/// it aggregates, and how many arguments it takes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSignature
+{
+    pub name: String,
+    pub kind: FunctionKind,
+    pub min_args: usize,
+    /// `None` for variadic functions such as `COALESCE`.
+    pub max_args: Option<usize>,
+}
+
+impl FunctionSignature
+{
+    pub fn scalar(name: &str, min_args: usize, max_args: Option<usize>) -> Self
+    {
+        Self { name: name.to_uppercase(), kind: FunctionKind::Scalar, min_args, max_args }
+    }
+
+    pub fn aggregate(name: &str, min_args: usize, max_args: Option<usize>) -> Self
+    {
+        Self { name: name.to_uppercase(), kind: FunctionKind::Aggregate, min_args, max_args }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool
+    {
+        count >= self.min_args && self.max_args.is_none_or(|max| count <= max)
+    }
+
+    /// The accepted argument counts in words, e.g. "1 to 2 arguments".
+    pub fn arity(&self) -> String
+    {
+        let plural = |n: usize| if n == 1 { "argument" } else { "arguments" };
+        match self.max_args
+        {
+            Some(max) if max == self.min_args => format!("exactly {} {}", max, plural(max)),
+            Some(max) => format!("{} to {} arguments", self.min_args, max),
+            None => format!("at least {} {}", self.min_args, plural(self.min_args)),
+        }
+    }
+}
+
+/// The functions the parser checks calls against. Names are matched without
+/// regard to case; calls to functions it does not know are accepted as
+/// written.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionRegistry
+{
+    functions: HashMap<String, FunctionSignature>,
+}
+
+/// Built-in functions as (name, kind, min args, max args).
+const BUILTINS: &[(&str, FunctionKind, usize, Option<usize>)] = &[
+    ("COUNT", FunctionKind::Aggregate, 1, Some(1)),
+    ("SUM", FunctionKind::Aggregate, 1, Some(1)),
+    ("AVG", FunctionKind::Aggregate, 1, Some(1)),
+    ("MIN", FunctionKind::Aggregate, 1, Some(1)),
+    ("MAX", FunctionKind::Aggregate, 1, Some(1)),
+    ("ARRAY_AGG", FunctionKind::Aggregate, 1, Some(1)),
+    ("STRING_AGG", FunctionKind::Aggregate, 2, Some(2)),
+    ("LOWER", FunctionKind::Scalar, 1, Some(1)),
+    ("UPPER", FunctionKind::Scalar, 1, Some(1)),
+    ("LENGTH", FunctionKind::Scalar, 1, Some(1)),
+    ("ABS", FunctionKind::Scalar, 1, Some(1)),
+    ("ROUND", FunctionKind::Scalar, 1, Some(2)),
+    ("SUBSTRING", FunctionKind::Scalar, 2, Some(3)),
+    ("REPLACE", FunctionKind::Scalar, 3, Some(3)),
+    ("CONCAT", FunctionKind::Scalar, 1, None),
+    ("COALESCE", FunctionKind::Scalar, 1, None),
+    ("NULLIF", FunctionKind::Scalar, 2, Some(2)),
+    ("GREATEST", FunctionKind::Scalar, 1, None),
+    ("LEAST", FunctionKind::Scalar, 1, None),
+    ("DATE_TRUNC", FunctionKind::Scalar, 2, Some(2)),
+    ("NOW", FunctionKind::Scalar, 0, Some(0)),
+];
+
+impl FunctionRegistry
+{
+    /// A registry that knows no functions, so no call is checked.
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// A registry with the common aggregates and scalar functions.
+    pub fn with_builtins() -> Self
+    {
+        let mut registry = Self::new();
+        for &(name, kind, min_args, max_args) in BUILTINS
+        {
+            registry.register(FunctionSignature { name: name.to_string(), kind, min_args, max_args });
+        }
+        registry
+    }
+
+    /// The shared built-in registry parsers use unless given another.
+    pub(crate) fn builtins() -> &'static FunctionRegistry
+    {
+        static BUILTIN: OnceLock<FunctionRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(Self::with_builtins)
+    }
+
+    /// Adds `signature`, replacing any function of the same name.
+    pub fn register(&mut self, signature: FunctionSignature) -> &mut Self
+    {
+        self.functions.insert(signature.name.to_uppercase(), signature);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FunctionSignature>
+    {
+        self.functions.get(&name.to_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtins_and_registration() {
+        let mut registry = FunctionRegistry::with_builtins();
+        let lower = registry.get("lower").unwrap();
+        assert_eq!(lower.name, "LOWER");
+        assert!(lower.accepts(1) && !lower.accepts(2));
+        assert_eq!(lower.arity(), "exactly 1 argument");
+        assert_eq!(registry.get("coalesce").unwrap().arity(), "at least 1 argument");
+        assert_eq!(registry.get("round").unwrap().arity(), "1 to 2 arguments");
+        assert!(registry.get("my_udf").is_none());
+
+        registry.register(FunctionSignature::scalar("my_udf", 0, Some(2)));
+        assert_eq!(registry.get("MY_UDF").unwrap().name, "MY_UDF");
+        assert!(FunctionRegistry::new().get("lower").is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//Created by: Nisha Murali (Requested not to clone / re-use the code)
+//lib.rs (Public API of the SQL parser)
+//! A small SQL parser: tokenizer, Pratt expression parser and AST.
+//!
+//! ```
+//! use sql_parser::ast::Statement;
+//!
+//! let statements = sql_parser::parse_sql("SELECT name FROM users WHERE age > 18;").unwrap();
+//! assert!(matches!(statements[0], Statement::Query(_)));
+//! assert_eq!(statements[0].to_string(), "SELECT name FROM users WHERE age > 18");
+//! ```
+pub mod ast;
+pub mod dialect;
+pub mod error;
+pub mod functions;
+pub mod parser;
+pub mod span;
+pub mod tokenizer;
+pub mod visitor;
+mod display;
+mod pratt;
+#[cfg(test)]
+mod test_helpers;
+
+pub use ast::Statement;
+pub use dialect::Dialect;
+pub use display::DialectDisplay;
+pub use error::{ParseError, StatementError};
+pub use parser::SQLParser;
+pub use tokenizer::Tokenizer;
+
+use span::Span;
+use tokenizer::{Token, SpannedToken};
+
+/// Parses a script of `;`-separated statements. If several statements are
+/// broken only the first error is returned; use [`parse_script_with_dialect`]
+/// to get all of them.
+pub fn parse_sql(sql: &str) -> Result<Vec<Statement>, ParseError>
+{
+    parse_sql_with_dialect(sql, &dialect::GenericDialect)
+}
+
+/// Like [`parse_sql`], but only accepts what `dialect` allows.
+pub fn parse_sql_with_dialect(sql: &str, dialect: &dyn Dialect) -> Result<Vec<Statement>, ParseError>
+{
+    parse_script_with_dialect(sql, dialect)
+        .map_err(|errors| errors.into_iter().next().expect("a failed script has at least one error").error)
+}
+
+/// Parses a script of `;`-separated statements and reports every broken
+/// statement with its index, whether the tokenizer or the parser rejected
+/// it. After text the tokenizer cannot read, such as a malformed number,
+/// reading carries on at the next `;`.
+pub fn parse_script_with_dialect(sql: &str, dialect: &dyn Dialect) -> Result<Vec<Statement>, Vec<StatementError>>
+{
+    // Widens the span of the statement being skipped.
+    fn stretch(rejected: &mut [(usize, StatementError)], span: Span)
+    {
+        if let Some((_, last)) = rejected.last_mut()
+        {
+            last.span = last.span.union(span);
+        }
+    }
+
+    let mut tokenizer = Tokenizer::new(sql).dialect(dialect);
+    let mut tokens: Vec<SpannedToken> = Vec::new();
+    // Statements the tokenizer failed on, with the number of tokens kept
+    // before each of them.
+    let mut rejected: Vec<(usize, StatementError)> = Vec::new();
+    let mut skipping = false;
+    loop
+    {
+        let token = match tokenizer.next_token()
+        {
+            Ok(token) => token,
+            Err(error) if skipping =>
+            {
+                stretch(&mut rejected, error.span);
+                continue;
+            }
+            Err(error) =>
+            {
+                // Drop what was read of the statement and skip the rest of it.
+                let start = tokens.iter().rposition(|t| t.token == Token::Semicolon).map_or(0, |i| i + 1);
+                let span = tokens.get(start).map_or(error.span, |t| t.span.union(error.span));
+                tokens.truncate(start);
+                rejected.push((start, StatementError { index: 0, span, error }));
+                skipping = true;
+                continue;
+            }
+        };
+        if skipping && !matches!(token.token, Token::Semicolon | Token::Eof)
+        {
+            stretch(&mut rejected, token.span);
+            continue;
+        }
+        skipping = false;
+        let done = token.token == Token::Eof;
+        tokens.push(token);
+        if done
+        {
+            break;
+        }
+    }
+
+    let parsed = SQLParser::new(&tokens).dialect(dialect).parse_script();
+    if rejected.is_empty()
+    {
+        return parsed;
+    }
+
+    // The parser numbered only the statements it saw; fit the rejected ones
+    // in between. `before` counts the parser's statements ahead of each.
+    let rejected: Vec<(usize, StatementError)> = rejected.into_iter()
+        .map(|(start, error)|
+        {
+            let before = tokens[..start].split(|t| t.token == Token::Semicolon).filter(|s| !s.is_empty()).count();
+            (before, error)
+        })
+        .collect();
+    let mut errors = parsed.err().unwrap_or_default();
+    for error in &mut errors
+    {
+        error.index += rejected.iter().filter(|(before, _)| *before <= error.index).count();
+    }
+    for (shift, (before, mut error)) in rejected.into_iter().enumerate()
+    {
+        error.index = before + shift;
+        errors.push(error);
+    }
+    errors.sort_by_key(|error| error.index);
+    Err(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sql_returns_first_error() {
+        let err = parse_sql("SELECT * FROM t; DELETE t; UPDATE").unwrap_err();
+        assert_eq!(err.kind, error::ParseErrorKind::ExpectedKeyword("FROM".to_string()));
+        assert_eq!(err.span.start.column, 25);
+    }
+
+    #[test]
+    fn test_script_reports_tokenizer_errors_per_statement() {
+        let script = "SELECT 1e+ FROM t; SELECT a FROM t; SELECT FROM t;\nSELECT 2 ! 3; SELECT 'it;s' FROM t; DELETE FROM t WHERE a = 'oops";
+        let errors = parse_script_with_dialect(script, &dialect::GenericDialect).unwrap_err();
+
+        assert_eq!(errors.iter().map(|e| e.index).collect::<Vec<_>>(), vec![0, 2, 3, 5]);
+        assert_eq!(errors[0].error.kind, error::ParseErrorKind::InvalidToken("Missing digits in exponent of number literal".to_string()));
+        assert_eq!(&script[errors[0].span.start.offset..errors[0].span.end.offset], "SELECT 1e+ FROM t");
+        assert_eq!(errors[1].error.kind, error::ParseErrorKind::InvalidExpression("Unexpected token: FROM".to_string()));
+        assert_eq!(errors[3].error.kind, error::ParseErrorKind::InvalidToken("Unterminated string literal".to_string()));
+        assert!(errors[3].render(script).starts_with("in statement 6:\n"));
+        assert!(parse_script_with_dialect("SELECT a FROM t;;SELECT b FROM u", &dialect::GenericDialect).is_ok());
+    }
+
+    #[test]
+    fn test_equality_ignores_spans() {
+        let compact = parse_sql("SELECT a FROM t WHERE b = 1").unwrap();
+        let spread = parse_sql("SELECT  a\nFROM t\nWHERE b=1").unwrap();
+        assert_ne!(compact[0].span(), spread[0].span());
+        assert_eq!(compact, spread);
+        assert_ne!(compact, parse_sql("SELECT a FROM t WHERE b = 2").unwrap());
+    }
+
+    #[test]
+    fn test_parse_sql_with_comments() {
+        let script = "-- migration 0042\n/* adds the users table */\nCREATE TABLE users (id INT); -- done\n";
+        assert_eq!(parse_sql(script).unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_shape() {
+        let statement = parse_sql("DELETE FROM t WHERE NOT id = 1").unwrap().remove(0);
+        let json = serde_json::to_value(&statement).unwrap();
+        let span = |start: usize, end: usize| serde_json::json!({
+            "start": { "offset": start, "line": 1, "column": start + 1 },
+            "end": { "offset": end, "line": 1, "column": end + 1 },
+        });
+        assert_eq!(json, serde_json::json!({
+            "Delete": {
+                "with": null,
+                "table_name": "t",
+                "selection": {
+                    "kind": { "UnaryOperation": {
+                        "operator": "Not",
+                        "operand": {
+                            "kind": { "BinaryOperation": {
+                                "left_operand": { "kind": { "Identifier": "id" }, "span": span(24, 26) },
+                                "operator": "Equals",
+                                "right_operand": { "kind": { "Number": "1" }, "span": span(29, 30) },
+                            }},
+                            "span": span(24, 30),
+                        },
+                    }},
+                    "span": span(20, 30),
+                },
+                "span": span(0, 30),
+            }
+        }));
+
+        let back: Statement = serde_json::from_value(json).unwrap();
+        assert_eq!(back, statement);
+    }
+}
@@ -1,58 +1,367 @@
 //Created by: Nisha Murali (Requested not to clone / re-use the code)
-//main.rs (CLI Loop and entry point) 
-mod tokenizer;
-mod parser;
-mod pratt;
-mod error;
-mod ast;
+//main.rs (CLI Loop and entry point)
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::ExitCode;
+use sql_parser::{Tokenizer, Dialect, Statement};
+use sql_parser::dialect::{dialect_from_name, GenericDialect};
+use sql_parser::tokenizer::Token;
 
+const USAGE: &str = "\
+Usage: Nisha_Murali [OPTIONS] [FILE]
 
-use std::io::{self, Write};
-use tokenizer::{Tokenizer, Token};
-use parser::SQLParser;
+Parses the SQL in FILE, on stdin when it is piped, or typed at a sql> prompt.
 
-fn main() 
+Options:
+  -f, --file <PATH>      read statements from PATH
+      --format <FORMAT>  debug (default), sql, json or tokens
+  -d, --dialect <NAME>   generic (default), ansi, postgres, mysql, sqlite or mssql
+  -q, --quiet            do not print the banner of the sql> prompt
+  -h, --help             show this help
+
+Exit status is 0 when everything parses, 1 on a syntax error and 2 on a
+usage or I/O error.";
+
+/// How parsed input is printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format
 {
-    println!("🔷 Welcome to Basic SQL Parser CLI By Nisha Murali");
-    println!("Type a SQL query or 'exit' to quit.\n");
+    Debug,
+    Sql,
+    #[cfg(feature = "serde")]
+    Json,
+    Tokens,
+}
 
-    loop 
+impl Format
+{
+    fn from_name(name: &str) -> Result<Self, String>
     {
-        print!("sql> ");
-        io::stdout().flush().unwrap();
+        match name
+        {
+            "debug" => Ok(Format::Debug),
+            "sql" => Ok(Format::Sql),
+            #[cfg(feature = "serde")]
+            "json" => Ok(Format::Json),
+            #[cfg(not(feature = "serde"))]
+            "json" => Err("--format json needs a build with the serde feature (cargo build --features serde)".to_string()),
+            "tokens" => Ok(Format::Tokens),
+            _ => Err(format!("unknown format '{}', expected debug, sql, json or tokens", name)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Options
+{
+    file: Option<String>,
+    format: Format,
+    dialect: Box<dyn Dialect>,
+    quiet: bool,
+    help: bool,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String>
+{
+    let mut options = Options
+    {
+        file: None,
+        format: Format::Debug,
+        dialect: Box::new(GenericDialect),
+        quiet: false,
+        help: false,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next()
+    {
+        // Long options also take their value as `--format=sql`.
+        let (flag, inline_value) = match arg.split_once('=')
+        {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || inline_value.clone()
+            .or_else(|| args.next())
+            .ok_or_else(|| format!("{} needs a value", flag));
+
+        match flag.as_str()
+        {
+            "-f" | "--file" => options.file = Some(value()?),
+            "--format" => options.format = Format::from_name(&value()?)?,
+            "-d" | "--dialect" =>
+            {
+                let name = value()?;
+                options.dialect = dialect_from_name(&name).ok_or_else(|| format!("unknown dialect '{}'", name))?;
+            }
+            "-q" | "--quiet" => options.quiet = true,
+            "-h" | "--help" => options.help = true,
+            _ if !arg.starts_with('-') && options.file.is_none() => options.file = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    Ok(options)
+}
+
+fn main() -> ExitCode
+{
+    let options = match parse_args(std::env::args().skip(1))
+    {
+        Ok(options) => options,
+        Err(e) =>
+        {
+            eprintln!("error: {}\nRun with --help for usage.", e);
+            return ExitCode::from(2);
+        }
+    };
+    if options.help
+    {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let source = match &options.file
+    {
+        Some(path) => match std::fs::read_to_string(path)
+        {
+            Ok(source) => source,
+            Err(e) =>
+            {
+                eprintln!("error: cannot read {}: {}", path, e);
+                return ExitCode::from(2);
+            }
+        },
+        None if !io::stdin().is_terminal() =>
+        {
+            let mut source = String::new();
+            if let Err(e) = io::stdin().read_to_string(&mut source)
+            {
+                eprintln!("error: cannot read stdin: {}", e);
+                return ExitCode::from(2);
+            }
+            source
+        }
+        None =>
+        {
+            repl(options);
+            return ExitCode::SUCCESS;
+        }
+    };
+
+    if process(&source, &options, "")
+    {
+        ExitCode::SUCCESS
+    }
+    else
+    {
+        ExitCode::FAILURE
+    }
+}
 
-        let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() 
+/// Whether `buffer` ends in a `;` outside any string or comment, so the
+/// REPL has whole statements to parse. Input that stops inside a string or
+/// comment needs more lines; other errors are reported straight away.
+fn is_complete(buffer: &str, dialect: &dyn Dialect) -> bool
+{
+    let mut tokenizer = Tokenizer::new(buffer).dialect(dialect);
+    let mut last = Token::Eof;
+    loop
+    {
+        match tokenizer.next_token()
         {
-            eprintln!("Failed to read input.");
-            continue;
+            Ok(token) if token.token == Token::Eof => return last == Token::Semicolon,
+            Ok(token) => last = token.token,
+            Err(e) => return e.span.end.offset < buffer.len(),
         }
+    }
+}
 
-        let input = input.trim();
-        if input.eq_ignore_ascii_case("exit") 
+/// Handles a `\command` typed at the prompt.
+fn meta_command(command: &str, options: &mut Options)
+{
+    let format = match command
+    {
+        "\\ast" => Format::Debug,
+        "\\sql" => Format::Sql,
+        "\\tokens" => Format::Tokens,
+        #[cfg(feature = "serde")]
+        "\\json" => Format::Json,
+        #[cfg(not(feature = "serde"))]
+        "\\json" =>
+        {
+            eprintln!("❌ JSON output needs a build with the serde feature");
+            return;
+        }
+        _ =>
         {
-            println!("👋Thank you for using, Goodbye!");
-            break;
+            eprintln!("❌ Unknown command {}, try \\ast, \\sql, \\json or \\tokens", command);
+            return;
         }
+    };
+    options.format = format;
+    println!("Output is now {}.", &command[1..]);
+}
+
+fn repl(mut options: Options)
+{
+    if !options.quiet
+    {
+        println!("🔷 Welcome to Basic SQL Parser CLI By Nisha Murali");
+        println!("End statements with ';'. \\ast, \\sql, \\json or \\tokens change the output, 'exit' quits.\n");
+    }
 
-        let mut tokenizer = Tokenizer::new(input);
-        let mut tokens = Vec::new();
+    let mut buffer = String::new();
+    loop
+    {
+        print!("{}", if buffer.is_empty() { "sql> " } else { "...> " });
+        io::stdout().flush().unwrap();
 
-        loop 
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line)
         {
-            let token = tokenizer.next_token();
-            tokens.push(token.clone());
-            if token == Token::Eof 
+            Ok(0) =>
             {
+                // Input ended without a closing `;`: parse what was typed
+                // rather than dropping it.
+                if !buffer.trim().is_empty()
+                {
+                    println!();
+                    process(&buffer, &options, "❌ ");
+                }
                 break;
             }
+            Ok(_) => {}
+            Err(_) =>
+            {
+                eprintln!("Failed to read input.");
+                continue;
+            }
+        }
+
+        if buffer.is_empty()
+        {
+            let command = line.trim();
+            if command.is_empty()
+            {
+                continue;
+            }
+            if command.eq_ignore_ascii_case("exit")
+            {
+                println!("👋Thank you for using, Goodbye!");
+                break;
+            }
+            if command.starts_with('\\')
+            {
+                meta_command(command, &mut options);
+                continue;
+            }
+        }
+
+        buffer.push_str(&line);
+        if is_complete(&buffer, options.dialect.as_ref())
+        {
+            process(&buffer, &options, "❌ ");
+            buffer.clear();
+        }
+    }
+}
+
+/// Parses `source` and prints the result in the chosen format, or the errors
+/// (each prefixed with `error_prefix`) on stderr. Returns whether it parsed.
+fn process(source: &str, options: &Options, error_prefix: &str) -> bool
+{
+    if options.format == Format::Tokens
+    {
+        // The tokens are worth seeing even when they do not parse.
+        if let Ok(tokens) = Tokenizer::new(source).dialect(options.dialect.as_ref()).tokenize()
+        {
+            for token in tokens.iter().filter(|t| t.token != Token::Eof)
+            {
+                println!("{}:{}\t{:?}", token.span.start.line, token.span.start.column, token.token);
+            }
+        }
+    }
+
+    match sql_parser::parse_script_with_dialect(source, options.dialect.as_ref())
+    {
+        Ok(statements) =>
+        {
+            print_statements(&statements, options.format, options.dialect.as_ref());
+            true
+        }
+        Err(errors) =>
+        {
+            for e in errors
+            {
+                eprintln!("{}{}", error_prefix, e.render(source));
+            }
+            false
         }
+    }
+}
 
-        let mut parser = SQLParser::new(&tokens);
-        match parser.parse_statement() 
+fn print_statements(statements: &[Statement], format: Format, dialect: &dyn Dialect)
+{
+    match format
+    {
+        Format::Debug =>
+        {
+            for statement in statements
+            {
+                println!("{:#?}", statement);
+            }
+        }
+        Format::Sql =>
+        {
+            for statement in statements
+            {
+                println!("{};", statement.display(dialect));
+            }
+        }
+        #[cfg(feature = "serde")]
+        Format::Json =>
         {
-            Ok(statement) => println!("\n✅ Parsed Statement:\n{:#?}\n", statement),
-            Err(e) => eprintln!("❌ Error: {}\n", e),
+            println!("{}", serde_json::to_string_pretty(statements).expect("the AST always serializes"));
         }
+        Format::Tokens => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Options, String> {
+        parse_args(list.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = args(&["--format=sql", "-q", "-d", "mysql", "queries.sql"]).unwrap();
+        assert_eq!(options.format, Format::Sql);
+        assert!(options.quiet);
+        assert_eq!(options.dialect.name(), "MySQL");
+        assert_eq!(options.file.as_deref(), Some("queries.sql"));
+
+        assert_eq!(args(&["--format"]).unwrap_err(), "--format needs a value");
+        assert_eq!(args(&["--format", "xml"]).unwrap_err(), "unknown format 'xml', expected debug, sql, json or tokens");
+        assert_eq!(args(&["a.sql", "b.sql"]).unwrap_err(), "unexpected argument 'b.sql'");
+    }
+
+    #[test]
+    fn test_process_fails_on_syntax_errors_in_every_format() {
+        for format in ["tokens", "sql", "debug"] {
+            let options = args(&["--format", format]).unwrap();
+            assert!(!process("SELEC b;", &options, ""), "{}", format);
+            assert!(process("SELECT b FROM t;", &options, ""), "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_is_complete() {
+        let dialect = GenericDialect;
+        assert!(!is_complete("CREATE TABLE t (\n", &dialect));
+        assert!(is_complete("CREATE TABLE t (\n  id INT\n);\n", &dialect));
+        assert!(!is_complete("SELECT 'a;\n", &dialect));
+        assert!(!is_complete("SELECT a /* ; */\n", &dialect));
+        assert!(is_complete("SELECT a FROM t; -- done\n", &dialect));
     }
 }
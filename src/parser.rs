@@ -1,154 +1,1081 @@
 //Created by: Nisha Murali (Requested not to clone / re-use the code)
 //parser.rs ( Parses SELECT and CREATE TABLE into abstarct syntax tree )
-use crate::tokenizer::{Token, Keyword};
-use crate::ast::{Statement, Expression, DBType, TableColumn, Constraint};
+use crate::tokenizer::{Tokenizer, Token, Keyword, SpannedToken};
+use crate::ast::{
+    Statement, Expression, SelectItem, TableReference, JoinOperator, JoinConstraint,
+    InsertSource, Assignment, DBType, TableColumn, Constraint, ExpressionKind,
+    OrderByItem, OrderDirection, NullsOrder, With, Cte, CteMaterialization, Query, Select, SetExpr,
+    SetOperator, SetQuantifier,
+};
 use crate::pratt::PrattParser;
-use crate::error::ParseError;
+use crate::dialect::{Dialect, GenericDialect};
+use crate::functions::FunctionRegistry;
+use crate::error::{ParseError, ParseErrorKind, StatementError};
+use crate::span::Span;
+
+/// Parses the query at the start of `tokens` for the Pratt parser, which
+/// meets subqueries inside expressions. Returns the statement and how many
+/// tokens it used.
+pub(crate) fn parse_subquery(tokens: &[SpannedToken], dialect: &dyn Dialect, functions: &FunctionRegistry) -> Result<(Query, usize), ParseError> 
+{
+    let mut parser = SQLParser::new(tokens).dialect(dialect).functions(functions);
+    let query = parser.parse_query()?;
+    Ok((query, parser.position))
+}
+
+/// Puts `with` on the INSERT, UPDATE or DELETE that followed it and
+/// stretches the statement's span back to the WITH keyword at `start`.
+fn attach_with(mut statement: Statement, with: With, start: Span) -> Statement 
+{
+    match &mut statement 
+    {
+        Statement::Insert { with: slot, span, .. }
+        | Statement::Update { with: slot, span, .. }
+        | Statement::Delete { with: slot, span, .. } => 
+        {
+            *slot = Some(with);
+            *span = start.union(*span);
+        }
+        Statement::Query(_) | Statement::CreateTable { .. } => unreachable!("queries keep their own WITH clause"),
+    }
+    statement
+}
+
+/// How many columns `body` returns, or `None` when a wildcard hides it.
+fn column_count(body: &SetExpr) -> Option<usize> 
+{
+    let columns = body.columns();
+    columns.iter().all(|c| matches!(c, SelectItem::Expression { .. })).then_some(columns.len())
+}
+
+/// The LIMIT, OFFSET and FETCH counts of a query.
+type RowLimits = (Option<Expression>, Option<Expression>, Option<Expression>);
 
 pub struct SQLParser<'a> 
 {
-    tokens: &'a [Token],
+    tokens: &'a [SpannedToken],
     position: usize,
+    dialect: &'a dyn Dialect,
+    functions: &'a FunctionRegistry,
 }
 
-impl<'a> SQLParser<'a> 
-{
-    pub fn new(tokens: &'a [Token]) -> Self 
-    {
-        Self { tokens, position: 0 }
+impl<'a> SQLParser<'a> 
+{
+    pub fn new(tokens: &'a [SpannedToken]) -> Self 
+    {
+        Self { tokens, position: 0, dialect: &GenericDialect, functions: FunctionRegistry::builtins() }
+    }
+
+    /// Only accept what `dialect` allows. Use the same dialect as the
+    /// tokenizer that produced the tokens.
+    pub fn dialect(mut self, dialect: &'a dyn Dialect) -> Self 
+    {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Check function calls against `functions` instead of the built-in
+    /// registry.
+    pub fn functions(mut self, functions: &'a FunctionRegistry) -> Self 
+    {
+        self.functions = functions;
+        self
+    }
+
+    fn peek(&self) -> Option<&Token> 
+    {
+        self.tokens.get(self.position).map(|t| &t.token)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> 
+    {
+        self.tokens.get(self.position + n).map(|t| &t.token)
+    }
+
+    fn advance(&mut self) -> Option<&Token> 
+    {
+        let token = self.tokens.get(self.position).map(|t| &t.token);
+        self.position += 1;
+        token
+    }
+
+    /// Span of the next token, or of the last one once the input is used up.
+    fn peek_span(&self) -> Span 
+    {
+        self.tokens.get(self.position)
+            .or(self.tokens.last())
+            .map(|t| t.span)
+            .unwrap_or_default()
+    }
+
+    /// Span of the most recently consumed token.
+    fn previous_span(&self) -> Span 
+    {
+        self.position.checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map(|t| t.span)
+            .unwrap_or_default()
+    }
+
+    /// An error pointing at the most recently consumed token.
+    fn error_at_previous(&self, kind: ParseErrorKind) -> ParseError 
+    {
+        match self.position.checked_sub(1).and_then(|i| self.tokens.get(i)) 
+        {
+            Some(token) => ParseError::at(kind, token),
+            None => ParseError::new(kind, self.peek_span()),
+        }
+    }
+
+    fn unexpected_end(&self) -> ParseError 
+    {
+        ParseError::new(ParseErrorKind::UnexpectedEnd, self.peek_span())
+    }
+
+    /// Whether the next token is `keyword`. Contextual keywords such as
+    /// FIRST, and keywords the dialect leaves unreserved, arrive as
+    /// identifiers, so those are matched by spelling. Quoted identifiers
+    /// never match.
+    fn peek_keyword(&self, keyword: &Keyword) -> bool 
+    {
+        match self.peek() 
+        {
+            Some(Token::Keyword(k)) => k == keyword,
+            Some(Token::Identifier(word)) => (keyword.is_contextual() || !self.dialect.is_reserved_keyword(keyword))
+                && Tokenizer::lookup_keyword(word).as_ref() == Some(keyword),
+            _ => false,
+        }
+    }
+
+    /// The outer join that LEFT, RIGHT or FULL would start here.
+    fn peek_outer_join(&self) -> Option<JoinOperator> 
+    {
+        if self.peek_keyword(&Keyword::Left) 
+        {
+            Some(JoinOperator::LeftOuter)
+        } 
+        else if self.peek_keyword(&Keyword::Right) 
+        {
+            Some(JoinOperator::RightOuter)
+        } 
+        else if self.peek_keyword(&Keyword::Full) 
+        {
+            Some(JoinOperator::FullOuter)
+        } 
+        else 
+        {
+            None
+        }
+    }
+
+    /// Consumes the next token if it is `keyword`.
+    fn parse_keyword(&mut self, keyword: &Keyword) -> bool 
+    {
+        let found = self.peek_keyword(keyword);
+        if found 
+        {
+            self.advance();
+        }
+        found
+    }
+
+    /// Consumes the next token and reports that `expected` should have been
+    /// there instead.
+    fn expected_keyword(&mut self, expected: &str) -> ParseError 
+    {
+        match self.advance() 
+        {
+            Some(_) => self.error_at_previous(ParseErrorKind::ExpectedKeyword(expected.to_string())),
+            None => self.unexpected_end(),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: Keyword) -> Result<(), ParseError> 
+    {
+        if self.parse_keyword(&keyword) 
+        {
+            Ok(())
+        } 
+        else 
+        {
+            Err(self.expected_keyword(&keyword.to_string()))
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<String, ParseError> 
+    {
+        match self.advance() 
+        {
+            Some(Token::Identifier(name) | Token::QuotedIdentifier(name)) => Ok(name.clone()),
+            Some(_) => Err(self.error_at_previous(ParseErrorKind::ExpectedIdentifier)),
+            None => Err(self.unexpected_end()),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> 
+    {
+        match self.advance() 
+        {
+            Some(t) if *t == expected => Ok(()),
+            Some(_) => Err(self.error_at_previous(ParseErrorKind::General(format!("Expected '{}'", expected)))),
+            None => Err(self.unexpected_end()),
+        }
+    }
+
+    /// Hands the remaining tokens to the Pratt parser and skips past
+    /// whatever it consumed.
+    fn parse_expression(&mut self) -> Result<Expression, ParseError> 
+    {
+        self.parse_expression_from(1)
+    }
+
+    /// Parses a single operand with no binary operator after it, as needed
+    /// for `TOP 10 *` where `*` is not a multiplication.
+    fn parse_operand(&mut self) -> Result<Expression, ParseError> 
+    {
+        self.parse_expression_from(u8::MAX)
+    }
+
+    fn parse_expression_from(&mut self, min_precedence: u8) -> Result<Expression, ParseError> 
+    {
+        let remaining = &self.tokens[self.position.min(self.tokens.len())..];
+        let mut expr_parser = PrattParser::new(remaining).dialect(self.dialect).functions(self.functions);
+        let expr = expr_parser.parse_expression(min_precedence)?;
+        self.position += expr_parser.position;
+        Ok(expr)
+    }
+
+    /// An error at the token just consumed, for syntax the dialect lacks.
+    fn unsupported(&self, what: &str) -> ParseError 
+    {
+        let kind = ParseErrorKind::General(format!("{} is not supported by the {} dialect", what, self.dialect.name()));
+        self.error_at_previous(kind)
+    }
+
+    fn at_end(&self) -> bool 
+    {
+        matches!(self.peek(), Some(Token::Eof) | None)
+    }
+
+    fn parse_optional_where(&mut self) -> Result<Option<Expression>, ParseError> 
+    {
+        if let Some(Token::Keyword(Keyword::Where)) = self.peek() 
+        {
+            self.advance();
+            return Ok(Some(self.parse_expression()?));
+        }
+        Ok(None)
+    }
+
+    /// Parses `(a, b, ...)`.
+    fn parse_parenthesized_identifiers(&mut self) -> Result<Vec<String>, ParseError> 
+    {
+        self.expect(Token::LeftParentheses)?;
+        let mut names = vec![self.expect_identifier()?];
+        while let Some(Token::Comma) = self.peek() 
+        {
+            self.advance();
+            names.push(self.expect_identifier()?);
+        }
+        self.expect(Token::RightParentheses)?;
+        Ok(names)
+    }
+
+    /// Parses a single statement, optionally terminated by `;`, and rejects
+    /// anything that follows it.
+    pub fn parse_statement(&mut self) -> Result<Statement, ParseError> 
+    {
+        let statement = self.parse_statement_body()?;
+        if let Some(Token::Semicolon) = self.peek() 
+        {
+            self.advance();
+        }
+        if !self.at_end() 
+        {
+            self.advance();
+            let kind = ParseErrorKind::General("Unexpected token after end of statement".to_string());
+            return Err(self.error_at_previous(kind).with_help("separate statements with ';'"));
+        }
+        Ok(statement)
+    }
+
+    /// Parses a whole script of `;`-separated statements. Parsing carries on
+    /// past a broken statement so that every error in the script is reported.
+    pub fn parse_script(&mut self) -> Result<Vec<Statement>, Vec<StatementError>> 
+    {
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+        let mut index = 0;
+
+        loop 
+        {
+            while let Some(Token::Semicolon) = self.peek() 
+            {
+                self.advance();
+            }
+            if self.at_end() 
+            {
+                break;
+            }
+
+            let start = self.peek_span();
+            let result = self.parse_statement_body().and_then(|statement| 
+            {
+                match self.peek() 
+                {
+                    Some(Token::Semicolon) | Some(Token::Eof) | None => Ok(statement),
+                    Some(_) => 
+                    {
+                        self.advance();
+                        let kind = ParseErrorKind::General("Expected ';' or end of input".to_string());
+                        Err(self.error_at_previous(kind).with_help("separate statements with ';'"))
+                    }
+                }
+            });
+
+            match result 
+            {
+                Ok(statement) => statements.push(statement),
+                Err(error) => 
+                {
+                    // Resynchronise on the next statement separator.
+                    while !self.at_end() && self.peek() != Some(&Token::Semicolon) 
+                    {
+                        self.advance();
+                    }
+                    let span = start.union(self.previous_span());
+                    errors.push(StatementError { index, span, error });
+                }
+            }
+            index += 1;
+        }
+
+        if errors.is_empty() 
+        {
+            Ok(statements)
+        } 
+        else 
+        {
+            Err(errors)
+        }
+    }
+
+    fn parse_statement_body(&mut self) -> Result<Statement, ParseError> 
+    {
+        self.check_statement_supported()?;
+        match self.peek() 
+        {
+            Some(Token::Keyword(Keyword::With)) => self.parse_with_statement(),
+            Some(Token::Keyword(Keyword::Select)) | Some(Token::LeftParentheses) => Ok(Statement::Query(Box::new(self.parse_query()?))),
+            Some(Token::Keyword(Keyword::Create)) => self.parse_create_table(),
+            Some(Token::Keyword(Keyword::Insert)) => self.parse_insert(),
+            Some(Token::Keyword(Keyword::Update)) => self.parse_update(),
+            Some(Token::Keyword(Keyword::Delete)) => self.parse_delete(),
+            Some(Token::Eof) | None => Err(ParseError::new(ParseErrorKind::General("Empty input".to_string()), self.peek_span())),
+            Some(tok) => 
+            {
+                let kind = ParseErrorKind::UnknownStartOfStatement(tok.to_string());
+                Err(ParseError::at(kind, &self.tokens[self.position])
+                    .with_help("statements start with SELECT, INSERT, UPDATE, DELETE, CREATE TABLE or WITH"))
+            }
+        }
+    }
+
+    /// Rejects a statement whose first keyword the dialect does not allow.
+    fn check_statement_supported(&self) -> Result<(), ParseError> 
+    {
+        if let Some(Token::Keyword(keyword)) = self.peek() 
+        {
+            if !self.dialect.supports_statement(keyword) 
+            {
+                let kind = ParseErrorKind::General(format!("{} statements are not supported by the {} dialect", keyword, self.dialect.name()));
+                return Err(ParseError::at(kind, &self.tokens[self.position]));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a WITH clause and the SELECT, INSERT, UPDATE or DELETE that
+    /// uses it.
+    fn parse_with_statement(&mut self) -> Result<Statement, ParseError> 
+    {
+        let start = self.peek_span();
+        let with = self.parse_with()?;
+        self.check_statement_supported()?;
+        let statement = match self.peek() 
+        {
+            Some(Token::Keyword(Keyword::Select)) | Some(Token::LeftParentheses) => 
+            {
+                return Ok(Statement::Query(Box::new(self.parse_query_body(Some(with), start)?)));
+            }
+            Some(Token::Keyword(Keyword::Insert)) => self.parse_insert()?,
+            Some(Token::Keyword(Keyword::Update)) => self.parse_update()?,
+            Some(Token::Keyword(Keyword::Delete)) => self.parse_delete()?,
+            _ => 
+            {
+                self.advance();
+                let kind = ParseErrorKind::ExpectedKeyword("SELECT, INSERT, UPDATE or DELETE".to_string());
+                return Err(self.error_at_previous(kind).with_help("a WITH clause must be followed by the statement that uses it"));
+            }
+        };
+        Ok(attach_with(statement, with, start))
+    }
+
+    /// Parses a query with an optional WITH clause, both as a statement and
+    /// wherever one is nested: subqueries, CTE bodies and INSERT ... SELECT.
+    fn parse_query(&mut self) -> Result<Query, ParseError> 
+    {
+        let start = self.peek_span();
+        let mut with = None;
+        if let Some(Token::Keyword(Keyword::With)) = self.peek() 
+        {
+            with = Some(self.parse_with()?);
+        }
+        self.parse_query_body(with, start)
+    }
+
+    /// Parses the body, ORDER BY and row limits of a query that started at
+    /// `start`, where `with` has already been read.
+    fn parse_query_body(&mut self, with: Option<With>, start: Span) -> Result<Query, ParseError> 
+    {
+        let body = self.parse_set_expr(1)?;
+
+        let mut order_by = Vec::new();
+        if let Some(Token::Keyword(Keyword::Order)) = self.peek() 
+        {
+            self.advance();
+            self.expect_keyword(Keyword::By)?;
+            order_by.push(self.parse_order_by_item(body.columns())?);
+            while let Some(Token::Comma) = self.peek() 
+            {
+                self.advance();
+                order_by.push(self.parse_order_by_item(body.columns())?);
+            }
+        }
+
+        let (limit, offset, fetch) = self.parse_limit_clauses()?;
+
+        Ok(Query 
+        {
+            with,
+            body,
+            order_by,
+            limit,
+            offset,
+            fetch,
+            span: start.union(self.previous_span()),
+        })
+    }
+
+    /// Parses query terms joined by UNION, INTERSECT and EXCEPT, taking only
+    /// operators that bind at least as tightly as `min_precedence`, so
+    /// INTERSECT groups before UNION and EXCEPT.
+    fn parse_set_expr(&mut self, min_precedence: u8) -> Result<SetExpr, ParseError> 
+    {
+        let mut left = self.parse_query_term()?;
+        loop 
+        {
+            let operator = match self.peek() 
+            {
+                Some(Token::Keyword(Keyword::Union)) => SetOperator::Union,
+                Some(Token::Keyword(Keyword::Intersect)) => SetOperator::Intersect,
+                Some(Token::Keyword(Keyword::Except)) => SetOperator::Except,
+                _ => break,
+            };
+            if operator.precedence() < min_precedence 
+            {
+                break;
+            }
+            let operator_index = self.position;
+            self.advance();
+
+            let quantifier = match self.peek() 
+            {
+                Some(Token::Keyword(Keyword::All)) => Some(SetQuantifier::All),
+                Some(Token::Keyword(Keyword::Distinct)) => Some(SetQuantifier::Distinct),
+                _ => None,
+            };
+            if quantifier.is_some() 
+            {
+                self.advance();
+            }
+
+            let right = self.parse_set_expr(operator.precedence() + 1)?;
+            if let (Some(left_count), Some(right_count)) = (column_count(&left), column_count(&right)) 
+            {
+                if left_count != right_count 
+                {
+                    let kind = ParseErrorKind::General(format!("each {} query must have the same number of columns", operator));
+                    return Err(ParseError::at(kind, &self.tokens[operator_index])
+                        .with_help(format!("the left side selects {} and the right side {} column(s)", left_count, right_count)));
+                }
+            }
+            left = SetExpr::SetOperation { left: Box::new(left), operator, quantifier, right: Box::new(right) };
+        }
+        Ok(left)
+    }
+
+    /// Parses a single SELECT or a parenthesized query.
+    fn parse_query_term(&mut self) -> Result<SetExpr, ParseError> 
+    {
+        if let Some(Token::LeftParentheses) = self.peek() 
+        {
+            self.advance();
+            let query = self.parse_query()?;
+            self.expect(Token::RightParentheses)?;
+            return Ok(SetExpr::Query(Box::new(query)));
+        }
+        Ok(SetExpr::Select(Box::new(self.parse_select()?)))
+    }
+
+    /// Parses `WITH [RECURSIVE] cte, ...`. CTE names must be unique.
+    fn parse_with(&mut self) -> Result<With, ParseError> 
+    {
+        self.expect_keyword(Keyword::With)?;
+        let recursive = matches!(self.peek(), Some(Token::Keyword(Keyword::Recursive)));
+        if recursive 
+        {
+            self.advance();
+        }
+
+        let mut ctes: Vec<Cte> = Vec::new();
+        loop 
+        {
+            let name_index = self.position;
+            let cte = self.parse_cte()?;
+            if ctes.iter().any(|other| other.name == cte.name) 
+            {
+                let kind = ParseErrorKind::General(format!("CTE {} is defined more than once", cte.name));
+                return Err(ParseError::at(kind, &self.tokens[name_index]));
+            }
+            ctes.push(cte);
+            match self.peek() 
+            {
+                Some(Token::Comma) => { self.advance(); }
+                _ => break,
+            }
+        }
+        Ok(With { recursive, ctes })
+    }
+
+    /// Parses `name [(columns)] AS [[NOT] MATERIALIZED] (query)`.
+    fn parse_cte(&mut self) -> Result<Cte, ParseError> 
+    {
+        let name = self.expect_identifier()?;
+        let mut columns = Vec::new();
+        if let Some(Token::LeftParentheses) = self.peek() 
+        {
+            columns = self.parse_parenthesized_identifiers()?;
+        }
+
+        match self.advance() 
+        {
+            Some(Token::Keyword(Keyword::As)) => {}
+            Some(_) => 
+            {
+                let kind = ParseErrorKind::ExpectedKeyword(Keyword::As.to_string());
+                return Err(self.error_at_previous(kind).with_help("a CTE is written name AS (SELECT ...)"));
+            }
+            None => return Err(self.unexpected_end()),
+        }
+
+        let materialized = match (self.peek(), self.peek_nth(1)) 
+        {
+            (Some(Token::Keyword(Keyword::Materialized)), _) => 
+            {
+                self.advance();
+                Some(CteMaterialization::Materialized)
+            }
+            (Some(Token::Keyword(Keyword::Not)), Some(Token::Keyword(Keyword::Materialized))) => 
+            {
+                self.advance();
+                self.advance();
+                Some(CteMaterialization::NotMaterialized)
+            }
+            _ => None,
+        };
+
+        self.expect(Token::LeftParentheses)?;
+        let query = Box::new(self.parse_query()?);
+        self.expect(Token::RightParentheses)?;
+        Ok(Cte { name, columns, materialized, query })
+    }
+
+    fn parse_select(&mut self) -> Result<Select, ParseError> 
+    {
+        let start = self.peek_span();
+        self.expect_keyword(Keyword::Select)?;
+
+        // TOP is only a keyword when a count follows; otherwise it is a column.
+        let mut top = None;
+        // Without TOP in the dialect, `top(1)` is a call to a function named top.
+        if self.peek_keyword(&Keyword::Top)
+            && (matches!(self.peek_nth(1), Some(Token::Number(_) | Token::Placeholder(_)))
+                || (self.dialect.supports_top() && matches!(self.peek_nth(1), Some(Token::LeftParentheses)))) 
+        {
+            self.advance();
+            if !self.dialect.supports_top() 
+            {
+                return Err(self.unsupported("TOP").with_help("use LIMIT or FETCH FIRST after ORDER BY"));
+            }
+            top = Some(self.parse_operand()?);
+        }
+
+        let mut columns = vec![self.parse_select_item()?];
+        while let Some(Token::Comma) = self.peek() 
+        {
+            self.advance();
+            columns.push(self.parse_select_item()?);
+        }
+
+        match self.advance() 
+        {
+            Some(Token::Keyword(Keyword::From)) => {}
+            Some(_) => 
+            {
+                let kind = ParseErrorKind::ExpectedKeyword(Keyword::From.to_string());
+                return Err(self.error_at_previous(kind).with_help("the column list must be followed by FROM"))
+            }
+            None => return Err(self.unexpected_end()),
+        }
+
+        let from = self.parse_from()?;
+
+        let selection = self.parse_optional_where()?;
+
+        let mut group_by = Vec::new();
+        if let Some(Token::Keyword(Keyword::Group)) = self.peek() 
+        {
+            self.advance();
+            self.expect_keyword(Keyword::By)?;
+            group_by.push(self.parse_expression()?);
+            while let Some(Token::Comma) = self.peek() 
+            {
+                self.advance();
+                group_by.push(self.parse_expression()?);
+            }
+        }
+
+        let mut having = None;
+        if let Some(Token::Keyword(Keyword::Having)) = self.peek() 
+        {
+            self.advance();
+            having = Some(self.parse_expression()?);
+        }
+
+        Ok(Select 
+        {
+            top,
+            columns,
+            from,
+            selection,
+            group_by,
+            having,
+            span: start.union(self.previous_span()),
+        })
+    }
+
+    /// Parses any of `LIMIT n`, `LIMIT m, n`, `OFFSET m [ROWS]` and
+    /// `FETCH {FIRST | NEXT} [n] {ROW | ROWS} ONLY`, returning the limit,
+    /// offset and fetch counts. LIMIT and FETCH cannot be combined.
+    fn parse_limit_clauses(&mut self) -> Result<RowLimits, ParseError> 
+    {
+        let mut limit = None;
+        let mut offset = None;
+        let mut fetch = None;
+        loop 
+        {
+            match self.peek() 
+            {
+                Some(Token::Keyword(Keyword::Limit)) if limit.is_none() && fetch.is_none() => 
+                {
+                    self.advance();
+                    if !self.dialect.supports_limit() 
+                    {
+                        return Err(self.unsupported("LIMIT").with_help("use FETCH FIRST n ROWS ONLY"));
+                    }
+                    let count = self.parse_expression()?;
+                    if let Some(Token::Comma) = self.peek() 
+                    {
+                        self.advance();
+                        if !self.dialect.supports_limit_comma() 
+                        {
+                            return Err(self.unsupported("LIMIT offset, count").with_help("write LIMIT count OFFSET offset"));
+                        }
+                        if offset.is_some() 
+                        {
+                            return Err(self.error_at_previous(ParseErrorKind::General("OFFSET is given twice".to_string())));
+                        }
+                        offset = Some(count);
+                        limit = Some(self.parse_expression()?);
+                    } 
+                    else 
+                    {
+                        limit = Some(count);
+                    }
+                }
+                Some(Token::Keyword(Keyword::Offset)) if offset.is_none() => 
+                {
+                    self.advance();
+                    offset = Some(self.parse_expression()?);
+                    if !self.parse_keyword(&Keyword::Row) 
+                    {
+                        self.parse_keyword(&Keyword::Rows);
+                    }
+                }
+                Some(Token::Keyword(Keyword::Fetch)) if fetch.is_none() && limit.is_none() => 
+                {
+                    self.advance();
+                    if !self.dialect.supports_fetch() 
+                    {
+                        return Err(self.unsupported("FETCH").with_help("use LIMIT n"));
+                    }
+                    if !self.parse_keyword(&Keyword::First) && !self.parse_keyword(&Keyword::Next) 
+                    {
+                        return Err(self.expected_keyword("FIRST or NEXT"));
+                    }
+                    let count = if self.peek_keyword(&Keyword::Row) || self.peek_keyword(&Keyword::Rows) 
+                    {
+                        Expression::new(ExpressionKind::Number("1".to_string()), self.peek_span())
+                    } 
+                    else 
+                    {
+                        self.parse_expression()?
+                    };
+                    if !self.parse_keyword(&Keyword::Row) && !self.parse_keyword(&Keyword::Rows) 
+                    {
+                        return Err(self.expected_keyword("ROWS"));
+                    }
+                    self.expect_keyword(Keyword::Only)?;
+                    fetch = Some(count);
+                }
+                _ => break,
+            }
+        }
+        Ok((limit, offset, fetch))
+    }
+
+    /// Parses `expr [ASC | DESC] [NULLS FIRST | NULLS LAST]`. A bare integer
+    /// is a position in `columns`, which is checked unless `*` makes the
+    /// number of columns unknown.
+    fn parse_order_by_item(&mut self, columns: &[SelectItem]) -> Result<OrderByItem, ParseError> 
+    {
+        let expr = self.parse_expression()?;
+        if let ExpressionKind::Number(n) = &expr.kind 
+        {
+            let wildcard = columns.iter().any(|c| !matches!(c, SelectItem::Expression { .. }));
+            let position = n.parse::<usize>().ok();
+            if !wildcard && !position.is_some_and(|p| (1..=columns.len()).contains(&p)) 
+            {
+                let kind = ParseErrorKind::General(format!("ORDER BY position {} is not in the select list", n));
+                return Err(ParseError::new(kind, expr.span)
+                    .with_help(format!("positions count the selected columns from 1 to {}", columns.len())));
+            }
+        }
+
+        let direction = match self.peek() 
+        {
+            Some(Token::Keyword(Keyword::Asc)) => Some(OrderDirection::Asc),
+            Some(Token::Keyword(Keyword::Desc)) => Some(OrderDirection::Desc),
+            _ => None,
+        };
+        if direction.is_some() 
+        {
+            self.advance();
+        }
+
+        let mut nulls = None;
+        if self.parse_keyword(&Keyword::Nulls) 
+        {
+            if self.parse_keyword(&Keyword::First) 
+            {
+                nulls = Some(NullsOrder::First);
+            } 
+            else if self.parse_keyword(&Keyword::Last) 
+            {
+                nulls = Some(NullsOrder::Last);
+            } 
+            else 
+            {
+                return Err(self.expected_keyword("FIRST or LAST"));
+            }
+        }
+
+        Ok(OrderByItem { expr, direction, nulls })
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem, ParseError> 
+    {
+        match (self.peek(), self.peek_nth(1), self.peek_nth(2)) 
+        {
+            (Some(Token::Multiply), _, _) => 
+            {
+                self.advance();
+                return Ok(SelectItem::Wildcard);
+            }
+            (Some(Token::Identifier(table) | Token::QuotedIdentifier(table)), Some(Token::Period), Some(Token::Multiply)) => 
+            {
+                let table = table.clone();
+                self.position += 3;
+                return Ok(SelectItem::QualifiedWildcard(table));
+            }
+            _ => {}
+        }
+
+        let expr = self.parse_expression()?;
+        let alias = self.parse_optional_alias()?;
+        Ok(SelectItem::Expression { expr, alias })
     }
 
-    fn peek(&self) -> Option<&Token> 
+    fn parse_from(&mut self) -> Result<Vec<TableReference>, ParseError> 
     {
-        self.tokens.get(self.position)
+        let mut tables = vec![self.parse_table_with_joins()?];
+        while let Some(Token::Comma) = self.peek() 
+        {
+            self.advance();
+            tables.push(self.parse_table_with_joins()?);
+        }
+        Ok(tables)
     }
 
-    fn advance(&mut self) -> Option<&Token> 
+    fn parse_table_with_joins(&mut self) -> Result<TableReference, ParseError> 
     {
-        let token = self.tokens.get(self.position);
-        self.position += 1;
-        token
+        let mut table = self.parse_table_factor()?;
+        while let Some((operator, natural)) = self.parse_join_operator()? 
+        {
+            let right = self.parse_table_factor()?;
+            let constraint = if natural 
+            {
+                JoinConstraint::Natural
+            } 
+            else if operator == JoinOperator::Cross 
+            {
+                JoinConstraint::None
+            } 
+            else 
+            {
+                self.parse_join_constraint()?
+            };
+            table = TableReference::Join 
+            {
+                left: Box::new(table),
+                operator,
+                right: Box::new(right),
+                constraint,
+            };
+        }
+        Ok(table)
     }
 
-    fn expect_keyword(&mut self, keyword: Keyword) -> Result<(), ParseError> 
+    /// Consumes a join keyword sequence such as `LEFT OUTER JOIN`, returning
+    /// the operator and whether it was NATURAL, or `None` if no join follows.
+    fn parse_join_operator(&mut self) -> Result<Option<(JoinOperator, bool)>, ParseError> 
     {
-        match self.advance() 
+        let natural = matches!(self.peek(), Some(Token::Keyword(Keyword::Natural)));
+        if natural 
         {
-            Some(Token::Keyword(k)) if *k == keyword => Ok(()),
-            Some(_) => Err(ParseError::ExpectedKeyword(format!("{:?}", keyword))),
-            None => Err(ParseError::UnexpectedEnd),
+            self.advance();
         }
+
+        let operator = match self.peek() 
+        {
+            Some(Token::Keyword(Keyword::Join)) => JoinOperator::Inner,
+            Some(Token::Keyword(Keyword::Inner)) => 
+            {
+                self.advance();
+                JoinOperator::Inner
+            }
+            Some(Token::Keyword(Keyword::Cross)) if !natural => 
+            {
+                self.advance();
+                JoinOperator::Cross
+            }
+            _ => match self.peek_outer_join() 
+            {
+                Some(operator) => 
+                {
+                    self.advance();
+                    if let Some(Token::Keyword(Keyword::Outer)) = self.peek() 
+                    {
+                        self.advance();
+                    }
+                    operator
+                }
+                None if natural => 
+                {
+                    self.advance();
+                    return Err(self.error_at_previous(ParseErrorKind::ExpectedKeyword(Keyword::Join.to_string())));
+                }
+                None => return Ok(None),
+            },
+        };
+        self.expect_keyword(Keyword::Join)?;
+        Ok(Some((operator, natural)))
     }
 
-    fn expect_identifier(&mut self) -> Result<String, ParseError> 
+    fn parse_join_constraint(&mut self) -> Result<JoinConstraint, ParseError> 
     {
         match self.advance() 
         {
-            Some(Token::Identifier(name)) => Ok(name.clone()),
-            Some(_) => Err(ParseError::ExpectedIdentifier),
-            None => Err(ParseError::UnexpectedEnd),
+            Some(Token::Keyword(Keyword::On)) => Ok(JoinConstraint::On(self.parse_expression()?)),
+            Some(Token::Keyword(Keyword::Using)) => Ok(JoinConstraint::Using(self.parse_parenthesized_identifiers()?)),
+            Some(_) => Err(self.error_at_previous(ParseErrorKind::ExpectedKeyword(Keyword::On.to_string()))
+                .with_help("joins need an ON condition or a USING (...) column list")),
+            None => Err(self.unexpected_end()),
         }
     }
 
-    fn expect(&mut self, expected: Token) -> Result<(), ParseError> 
+    fn parse_table_factor(&mut self) -> Result<TableReference, ParseError> 
     {
-        match self.advance() 
+        if let (Some(Token::LeftParentheses), Some(Token::Keyword(Keyword::Select | Keyword::With) | Token::LeftParentheses)) = (self.peek(), self.peek_nth(1)) 
         {
-            Some(t) if *t == expected => Ok(()),
-            Some(t) => Err(ParseError::General(format!("Expected {:?}, got {:?}", expected, t))),
-            None => Err(ParseError::UnexpectedEnd),
+            self.advance();
+            let subquery = Box::new(self.parse_query()?);
+            self.expect(Token::RightParentheses)?;
+            let alias = self.parse_optional_alias()?;
+            return Ok(TableReference::Derived { subquery, alias });
         }
+        let name = self.expect_identifier()?;
+        let alias = self.parse_optional_alias()?;
+        Ok(TableReference::Table { name, alias })
     }
 
-    pub fn parse_statement(&mut self) -> Result<Statement, ParseError> 
+    /// Parses `AS alias` or a bare `alias`.
+    fn parse_optional_alias(&mut self) -> Result<Option<String>, ParseError> 
     {
         match self.peek() 
         {
-            Some(Token::Keyword(Keyword::Select)) => self.parse_select(),
-            Some(Token::Keyword(Keyword::Create)) => self.parse_create_table(),
-            Some(tok) => Err(ParseError::UnknownStartOfStatement(format!("{:?}", tok))),
-            None => Err(ParseError::General("Empty input".to_string())),
+            Some(Token::Keyword(Keyword::As)) => 
+            {
+                self.advance();
+                Ok(Some(self.expect_identifier()?))
+            }
+            // An unreserved LEFT or RIGHT starts a join, not an alias; a quoted
+            // one never does.
+            Some(Token::Identifier(name) | Token::QuotedIdentifier(name)) if self.peek_outer_join().is_none() => 
+            {
+                let name = name.clone();
+                self.advance();
+                Ok(Some(name))
+            }
+            _ => Ok(None),
         }
     }
 
-    fn parse_select(&mut self) -> Result<Statement, ParseError> 
+    fn parse_insert(&mut self) -> Result<Statement, ParseError> 
     {
-        self.expect_keyword(Keyword::Select)?;
+        let start = self.peek_span();
+        self.expect_keyword(Keyword::Insert)?;
+        self.expect_keyword(Keyword::Into)?;
+        let table_name = self.expect_identifier()?;
 
+        // `INSERT INTO t (SELECT ...)` has no column list, only a query.
         let mut columns = Vec::new();
-        loop 
+        if matches!(self.peek(), Some(Token::LeftParentheses))
+            && !matches!(self.peek_nth(1), Some(Token::Keyword(Keyword::Select | Keyword::With) | Token::LeftParentheses)) 
+        {
+            columns = self.parse_parenthesized_identifiers()?;
+        }
+
+        let source = match self.peek() 
         {
-            match self.advance() 
+            Some(Token::Keyword(Keyword::Values)) => 
             {
-                Some(Token::Identifier(name)) => columns.push(name.clone()),
-                Some(Token::Comma) => continue,
-                Some(Token::Keyword(Keyword::From)) => break,
-                Some(tok) => 
+                self.advance();
+                let mut rows = vec![self.parse_values_row()?];
+                while let Some(Token::Comma) = self.peek() 
                 {
-                    return Err(ParseError::General(format!(
-                        "Unexpected token in column list: {:?}", tok)))
+                    self.advance();
+                    rows.push(self.parse_values_row()?);
                 }
-                None => return Err(ParseError::UnexpectedEnd),
+                InsertSource::Values(rows)
             }
-        }
+            Some(Token::Keyword(Keyword::Select | Keyword::With) | Token::LeftParentheses) => InsertSource::Query(Box::new(self.parse_query()?)),
+            _ => 
+            {
+                self.advance();
+                let kind = ParseErrorKind::ExpectedKeyword(Keyword::Values.to_string());
+                return Err(self.error_at_previous(kind).with_help("INSERT takes a VALUES list or a SELECT"));
+            }
+        };
 
-        let table = self.expect_identifier()?;
+        Ok(Statement::Insert 
+        {
+            with: None,
+            table_name,
+            columns,
+            source,
+            span: start.union(self.previous_span()),
+        })
+    }
 
-        let mut selection = None;
-        if let Some(Token::Identifier(name)) = self.peek() {
-    if name == "*" {
-        self.advance();
-        columns.push("*".to_string());
-        // Skip any trailing commas
-        while let Some(Token::Comma) = self.peek() {
+    fn parse_values_row(&mut self) -> Result<Vec<Expression>, ParseError> 
+    {
+        self.expect(Token::LeftParentheses)?;
+        let mut row = vec![self.parse_expression()?];
+        while let Some(Token::Comma) = self.peek() 
+        {
             self.advance();
+            row.push(self.parse_expression()?);
         }
-        self.expect_keyword(Keyword::From)?;
+        self.expect(Token::RightParentheses)?;
+        Ok(row)
     }
-}
 
-        //if let Some(Token::Keyword(Keyword::Where)) = self.peek() 
-        //{
-            //self.advance();
-            //let remaining = &self.tokens[self.position..];
-            //let mut expr_parser = PrattParser::new(remaining);
-           // let expr = expr_parser.parse_expression(1)
-             //   .map_err(ParseError::InvalidExpression)?;
-            //self.position += expr_parser.position;
-            //selection = Some(expr);
-        //}
-
-        let mut order_by = None;
-        if let Some(Token::Keyword(Keyword::Order)) = self.peek() 
+    fn parse_update(&mut self) -> Result<Statement, ParseError> 
+    {
+        let start = self.peek_span();
+        self.expect_keyword(Keyword::Update)?;
+        let table_name = self.expect_identifier()?;
+        self.expect_keyword(Keyword::Set)?;
+
+        let mut assignments = Vec::new();
+        loop 
         {
-            self.advance();
-            self.expect_keyword(Keyword::By)?;
-            let mut cols = Vec::new();
-            loop 
+            let column = self.expect_identifier()?;
+            self.expect(Token::Equal)?;
+            let value = self.parse_expression()?;
+            assignments.push(Assignment { column, value });
+
+            match self.peek() 
             {
-                match self.advance() 
-                {
-                    Some(Token::Identifier(name)) => cols.push(name.clone()),
-                    Some(Token::Comma) => continue,
-                    Some(Token::Semicolon) | Some(Token::Eof) => break,
-                    Some(tok) => return Err(ParseError::General(format!("Unexpected token in ORDER BY: {:?}", tok))),
-                    None => return Err(ParseError::UnexpectedEnd),
-                }
+                Some(Token::Comma) => { self.advance(); }
+                _ => break,
             }
-            order_by = Some(cols);
         }
 
-        Ok(Statement::Select 
+        let selection = self.parse_optional_where()?;
+
+        Ok(Statement::Update 
         {
-            columns,
-            table,
+            with: None,
+            table_name,
+            assignments,
             selection,
-            order_by,
+            span: start.union(self.previous_span()),
+        })
+    }
+
+    fn parse_delete(&mut self) -> Result<Statement, ParseError> 
+    {
+        let start = self.peek_span();
+        self.expect_keyword(Keyword::Delete)?;
+        self.expect_keyword(Keyword::From)?;
+        let table_name = self.expect_identifier()?;
+        let selection = self.parse_optional_where()?;
+
+        Ok(Statement::Delete 
+        {
+            with: None,
+            table_name,
+            selection,
+            span: start.union(self.previous_span()),
         })
     }
 
     fn parse_create_table(&mut self) -> Result<Statement, ParseError> 
     {
+        let start = self.peek_span();
         self.expect_keyword(Keyword::Create)?;
         self.expect_keyword(Keyword::Table)?;
 
@@ -160,14 +1087,16 @@ impl<'a> SQLParser<'a>
 
         loop 
         {
+            let column_start = self.peek_span();
             let name = self.expect_identifier()?;
 
             let data_type = match self.advance() {
                 Some(Token::Keyword(Keyword::Int)) => DBType::Int,
                 Some(Token::Keyword(Keyword::Bool)) => DBType::Bool,
                 Some(Token::Keyword(Keyword::Varchar)) => DBType::Varchar,
-                Some(tok) => return Err(ParseError::General(format!("Unknown type: {:?}", tok))),
-                None => return Err(ParseError::UnexpectedEnd),
+                Some(_) => return Err(self.error_at_previous(ParseErrorKind::General("Unknown type".to_string()))
+                    .with_help("supported types are INT, BOOL and VARCHAR")),
+                None => return Err(self.unexpected_end()),
             };
 
             let mut constraints = Vec::new();
@@ -193,12 +1122,7 @@ impl<'a> SQLParser<'a>
                         self.advance();
                         self.expect(Token::LeftParentheses)?;
 
-                        let remaining = &self.tokens[self.position..];
-                        let mut expr_parser = PrattParser::new(remaining);
-                        let expr = expr_parser.parse_expression(1)
-                            .map_err(ParseError::InvalidExpression)?;
-                        self.position += expr_parser.position;
-
+                        let expr = self.parse_expression()?;
                         self.expect(Token::RightParentheses)?;
                         constraints.push(Constraint::Check(expr));
                     }
@@ -211,25 +1135,22 @@ impl<'a> SQLParser<'a>
                 name,
                 data_type,
                 constraints,
+                span: column_start.union(self.previous_span()),
             });
 
             match self.advance() {
                 Some(Token::Comma) => continue,
                 Some(Token::RightParentheses) => break,
-                Some(tok) => return Err(ParseError::General(format!("Expected ',' or ')', got: {:?}", tok))),
-                None => return Err(ParseError::UnexpectedEnd),
+                Some(_) => return Err(self.error_at_previous(ParseErrorKind::General("Expected ',' or ')'".to_string()))),
+                None => return Err(self.unexpected_end()),
             }
         }
 
-        if let Some(Token::Semicolon) = self.peek() 
-        {
-            self.advance(); // Optional semicolon
-        }
-
         Ok(Statement::CreateTable 
         {
             table_name,
             columns,
+            span: start.union(self.previous_span()),
         })
     }
 }
@@ -238,6 +1159,8 @@ impl<'a> SQLParser<'a>
 mod tests {
     use super::*;
     use crate::tokenizer::Tokenizer;
+    use crate::ast::{ExpressionKind, FunctionArg, BinaryOperator, UnaryOperator, Quantifier};
+    use crate::test_helpers::{parse_statement, ident, number, string, placeholder, binary, table};
 
     #[test]
     fn test_select_star() {
@@ -245,8 +1168,8 @@ mod tests {
         let mut tokenizer = Tokenizer::new(input);
         let mut tokens = Vec::new();
         loop {
-            let tok = tokenizer.next_token();
-            if tok == Token::Eof {
+            let tok = tokenizer.next_token().unwrap();
+            if tok.token == Token::Eof {
                 break;
             }
             tokens.push(tok);
@@ -255,11 +1178,538 @@ mod tests {
         let stmt = parser.parse_statement().unwrap();
 
         match stmt {
-            Statement::Select { columns, table, .. } => {
-                assert_eq!(columns, vec!["*"]);
-                assert_eq!(table, "users");
+            Statement::Query(query) => match query.body {
+                SetExpr::Select(select) => {
+                    assert_eq!(select.columns, vec![SelectItem::Wildcard]);
+                    assert_eq!(select.from, vec![table("users", None)]);
+                }
+                _ => panic!("Expected a single SELECT"),
+            },
+            _ => panic!("Expected SELECT statement"),
+        }
+    }
+
+    #[test]
+    fn test_create_table_spans() {
+        let input = "CREATE TABLE t (\n  id INT PRIMARY KEY,\n  name VARCHAR\n);";
+        let tokens = Tokenizer::new(input).tokenize().unwrap();
+        let stmt = SQLParser::new(&tokens).parse_statement().unwrap();
+
+        let span = stmt.span();
+        assert_eq!(&input[span.start.offset..span.end.offset], input.trim_end_matches(';'));
+        match stmt {
+            Statement::CreateTable { columns, .. } => {
+                let id = columns[0].span;
+                assert_eq!(&input[id.start.offset..id.end.offset], "id INT PRIMARY KEY");
+                assert_eq!((id.start.line, id.start.column), (2, 3));
+                let name = columns[1].span;
+                assert_eq!(&input[name.start.offset..name.end.offset], "name VARCHAR");
+            }
+            _ => panic!("Expected CREATE TABLE statement"),
+        }
+    }
+
+    #[test]
+    fn test_error_points_at_offending_token() {
+        let input = "SELECT a, b c d FROM users";
+        let tokens = Tokenizer::new(input).tokenize().unwrap();
+        let err = SQLParser::new(&tokens).parse_statement().unwrap_err();
+
+        assert_eq!(err.kind, ParseErrorKind::ExpectedKeyword("FROM".to_string()));
+        assert_eq!(err.found.as_deref(), Some("d"));
+        assert_eq!((err.span.start.column, err.span.end.column), (15, 16));
+        assert!(err.render(input).contains("1 | SELECT a, b c d FROM users\n  |               ^ found `d`"));
+    }
+
+    fn query(input: &str) -> Query {
+        match parse_statement(input).unwrap() {
+            Statement::Query(query) => *query,
+            other => panic!("expected a query, got {:?}", other),
+        }
+    }
+
+    fn select(input: &str) -> Select {
+        match query(input).body {
+            SetExpr::Select(select) => *select,
+            other => panic!("expected a single SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_select_where_nested_predicates() {
+        let select = select("SELECT name FROM users WHERE NOT active = TRUE AND (age > 18 OR vip = 1) OR banned = 0");
+
+        let not_active = Expression::new(ExpressionKind::UnaryOperation {
+            operator: UnaryOperator::Not,
+            operand: Box::new(binary(
+                ident("active"),
+                BinaryOperator::Equals,
+                Expression::new(ExpressionKind::Boolean(true), Span::default()),
+            )),
+        }, Span::default());
+        let grouped = Expression::new(ExpressionKind::Grouped(Box::new(binary(
+            binary(ident("age"), BinaryOperator::GreaterThan, number(18)),
+            BinaryOperator::Or,
+            binary(ident("vip"), BinaryOperator::Equals, number(1)),
+        ))), Span::default());
+        let expected = binary(
+            binary(not_active, BinaryOperator::And, grouped),
+            BinaryOperator::Or,
+            binary(ident("banned"), BinaryOperator::Equals, number(0)),
+        );
+
+        assert_eq!(select.selection, Some(expected));
+    }
+
+    #[test]
+    fn test_select_where_then_order_by() {
+        let input = "SELECT name, age FROM users WHERE age > 18 ORDER BY name;";
+        let query = query(input);
+
+        assert_eq!(query.span.end.offset, input.len() - 1);
+        assert_eq!(query.order_by, vec![order(ident("name"), None, None)]);
+        match query.body {
+            SetExpr::Select(select) => {
+                assert_eq!(select.from, vec![table("users", None)]);
+                assert_eq!(select.selection, Some(binary(ident("age"), BinaryOperator::GreaterThan, number(18))));
+            }
+            other => panic!("expected a single SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_select_where_with_semicolon() {
+        let tokens = Tokenizer::new("SELECT * FROM users WHERE id = 7;").tokenize().unwrap();
+        let mut parser = SQLParser::new(&tokens);
+        let stmt = parser.parse_statement().unwrap();
+
+        assert_eq!(parser.peek(), Some(&Token::Eof));
+        match stmt {
+            Statement::Query(query) => {
+                assert!(query.order_by.is_empty());
+                assert!(matches!(query.body, SetExpr::Select(select)
+                    if select.selection == Some(binary(ident("id"), BinaryOperator::Equals, number(7)))));
+            }
+            _ => panic!("Expected SELECT statement"),
+        }
+    }
+
+    #[test]
+    fn test_select_where_missing_predicate() {
+        let err = parse_statement("SELECT * FROM users WHERE").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("Unexpected token: end of input".to_string()));
+    }
+
+    #[test]
+    fn test_select_projection_expressions() {
+        let select = select("SELECT price * qty AS total, u.name n, u.*, * FROM users");
+
+        let expected = vec![
+            SelectItem::Expression {
+                expr: binary(ident("price"), BinaryOperator::Multiply, ident("qty")),
+                alias: Some("total".to_string()),
+            },
+            SelectItem::Expression {
+                expr: Expression::new(ExpressionKind::CompoundIdentifier(vec!["u".to_string(), "name".to_string()]), Span::default()),
+                alias: Some("n".to_string()),
+            },
+            SelectItem::QualifiedWildcard("u".to_string()),
+            SelectItem::Wildcard,
+        ];
+        assert_eq!(select.columns, expected);
+    }
+
+    #[test]
+    fn test_select_joins() {
+        let select = select(
+            "SELECT * FROM orders o \
+             JOIN users AS u ON o.user_id = u.id \
+             LEFT OUTER JOIN refunds r USING (order_id, user_id) \
+             CROSS JOIN regions \
+             NATURAL FULL JOIN totals, audit",
+        );
+
+        let qualified = |t: &str, c: &str| Expression::new(
+            ExpressionKind::CompoundIdentifier(vec![t.to_string(), c.to_string()]),
+            Span::default(),
+        );
+        let join = |left, operator, right, constraint| TableReference::Join {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+            constraint,
+        };
+        let expected = join(
+            join(
+                join(
+                    join(
+                        table("orders", Some("o")),
+                        JoinOperator::Inner,
+                        table("users", Some("u")),
+                        JoinConstraint::On(binary(qualified("o", "user_id"), BinaryOperator::Equals, qualified("u", "id"))),
+                    ),
+                    JoinOperator::LeftOuter,
+                    table("refunds", Some("r")),
+                    JoinConstraint::Using(vec!["order_id".to_string(), "user_id".to_string()]),
+                ),
+                JoinOperator::Cross,
+                table("regions", None),
+                JoinConstraint::None,
+            ),
+            JoinOperator::FullOuter,
+            table("totals", None),
+            JoinConstraint::Natural,
+        );
+
+        assert_eq!(select.from, vec![expected, table("audit", None)]);
+    }
+
+    #[test]
+    fn test_join_requires_constraint() {
+        let err = parse_statement("SELECT * FROM a JOIN b WHERE x = 1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedKeyword("ON".to_string()));
+        assert_eq!(err.found.as_deref(), Some("WHERE"));
+    }
+
+    #[test]
+    fn test_select_group_by_having() {
+        let query = query("SELECT region, COUNT(DISTINCT user_id) AS buyers FROM orders GROUP BY region, year HAVING COUNT(*) > 10 ORDER BY region");
+
+        let count_star = Expression::new(ExpressionKind::Function {
+            name: "COUNT".to_string(),
+            args: vec![FunctionArg::Wildcard],
+            distinct: false,
+            filter: None,
+        }, Span::default());
+        assert_eq!(query.order_by, vec![order(ident("region"), None, None)]);
+        match query.body {
+            SetExpr::Select(select) => {
+                assert_eq!(select.columns[1], SelectItem::Expression {
+                    expr: Expression::new(ExpressionKind::Function {
+                        name: "COUNT".to_string(),
+                        args: vec![FunctionArg::Expression(ident("user_id"))],
+                        distinct: true,
+                        filter: None,
+                    }, Span::default()),
+                    alias: Some("buyers".to_string()),
+                });
+                assert_eq!(select.group_by, vec![ident("region"), ident("year")]);
+                assert_eq!(select.having, Some(binary(count_star, BinaryOperator::GreaterThan, number(10))));
             }
             _ => panic!("Expected SELECT statement"),
         }
     }
+
+    fn order(expr: Expression, direction: Option<OrderDirection>, nulls: Option<NullsOrder>) -> OrderByItem {
+        OrderByItem { expr, direction, nulls }
+    }
+
+    #[test]
+    fn test_order_by_directions_and_expressions() {
+        let query = query("SELECT name, age FROM users ORDER BY age DESC NULLS LAST, 1, LENGTH ASC, age * 2 NULLS FIRST");
+        assert_eq!(query.order_by, vec![
+            order(ident("age"), Some(OrderDirection::Desc), Some(NullsOrder::Last)),
+            order(number(1), None, None),
+            order(ident("LENGTH"), Some(OrderDirection::Asc), None),
+            order(binary(ident("age"), BinaryOperator::Multiply, number(2)), None, Some(NullsOrder::First)),
+        ]);
+
+        let err = parse_statement("SELECT name, age FROM users ORDER BY 3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("ORDER BY position 3 is not in the select list".to_string()));
+        assert_eq!(err.span.start.column, 38);
+        assert!(parse_statement("SELECT name FROM users ORDER BY 0").is_err());
+        assert!(parse_statement("SELECT name FROM users ORDER BY 99999999999999999999999").is_err());
+        // With * the number of columns is unknown.
+        assert!(parse_statement("SELECT * FROM users ORDER BY 3").is_ok());
+
+        let err = parse_statement("SELECT name FROM users ORDER BY name NULLS").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedKeyword("FIRST or LAST".to_string()));
+    }
+
+    #[test]
+    fn test_first_last_and_nulls_are_column_names_elsewhere() {
+        let query = query("SELECT first, last, nulls FROM people ORDER BY last NULLS FIRST, first nulls last FETCH FIRST 1 ROW ONLY");
+        assert_eq!(query.order_by, vec![
+            order(ident("last"), None, Some(NullsOrder::First)),
+            order(ident("first"), None, Some(NullsOrder::Last)),
+        ]);
+        assert_eq!(query.fetch, Some(number(1)));
+        assert_eq!(query.to_string(), "SELECT first, last, nulls FROM people ORDER BY last NULLS FIRST, first NULLS LAST FETCH FIRST 1 ROWS ONLY");
+    }
+
+    #[test]
+    fn test_quoted_identifiers_are_never_keywords() {
+        assert!(parse_statement("SELECT * FROM t ORDER BY a \"nulls\" first").is_err());
+        assert!(parse_statement("SELECT * FROM t ORDER BY a nulls \"first\"").is_err());
+        assert!(parse_statement("SELECT * FROM t FETCH \"first\" 1 ROWS ONLY").is_err());
+
+        let query = query("SELECT \"nulls\" \"first\" FROM t ORDER BY \"first\" NULLS LAST");
+        assert_eq!(query.order_by, vec![order(ident("first"), None, Some(NullsOrder::Last))]);
+    }
+
+    fn limits_of(input: &str) -> (Option<Expression>, Option<Expression>, Option<Expression>, Option<Expression>) {
+        let query = query(input);
+        (select(input).top, query.limit, query.offset, query.fetch)
+    }
+
+    #[test]
+    fn test_limit_offset_fetch() {
+        assert_eq!(limits_of("SELECT * FROM t ORDER BY id LIMIT 10 OFFSET 20"), (None, Some(number(10)), Some(number(20)), None));
+        assert_eq!(limits_of("SELECT * FROM t OFFSET $1 LIMIT ?"), (None, Some(placeholder("?")), Some(placeholder("$1")), None));
+        assert_eq!(limits_of("SELECT * FROM t LIMIT 20, :page_size"), (None, Some(placeholder(":page_size")), Some(number(20)), None));
+        assert_eq!(
+            limits_of("SELECT * FROM t OFFSET 5 ROWS FETCH NEXT 2 * 5 ROWS ONLY"),
+            (None, None, Some(number(5)), Some(binary(number(2), BinaryOperator::Multiply, number(5)))),
+        );
+        assert_eq!(limits_of("SELECT * FROM t FETCH FIRST ROW ONLY"), (None, None, None, Some(number(1))));
+        assert_eq!(limits_of("SELECT TOP 10 * FROM t"), (Some(number(10)), None, None, None));
+
+        assert!(parse_statement("SELECT * FROM t LIMIT 1 FETCH FIRST 1 ROWS ONLY").is_err());
+        let err = parse_statement("SELECT * FROM t FETCH FIRST 3 ONLY").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedKeyword("ROWS".to_string()));
+    }
+
+    #[test]
+    fn test_row_limit_words_are_column_names_elsewhere() {
+        let input = "SELECT top, row, rows, next, only FROM t ORDER BY top OFFSET 1 ROW FETCH NEXT 2 ROWS ONLY";
+        assert_eq!(limits_of(input), (None, None, Some(number(1)), Some(number(2))));
+        assert_eq!(parse_statement(input).unwrap().to_string(),
+            "SELECT top, row, rows, next, only FROM t ORDER BY top OFFSET 1 ROWS FETCH FIRST 2 ROWS ONLY");
+        assert_eq!(limits_of("SELECT TOP 3 top FROM t"), (Some(number(3)), None, None, None));
+    }
+
+    fn select_where(input: &str) -> ExpressionKind {
+        match select(input).selection {
+            Some(selection) => selection.kind,
+            None => panic!("expected a WHERE clause in {}", input),
+        }
+    }
+
+    #[test]
+    fn test_subqueries_in_expressions() {
+        match select_where("SELECT * FROM t WHERE a NOT IN (SELECT b FROM u WHERE u.x = t.x)") {
+            ExpressionKind::InSubquery { expr, subquery, negated } => {
+                assert_eq!(*expr, ident("a"));
+                assert!(negated);
+                assert_eq!(subquery.to_string(), "SELECT b FROM u WHERE u.x = t.x");
+            }
+            other => panic!("expected IN subquery, got {:?}", other),
+        }
+
+        assert_eq!(select_where("SELECT * FROM t WHERE a + 1 IN (1, 2)"), ExpressionKind::InList {
+            expr: Box::new(binary(ident("a"), BinaryOperator::Add, number(1))),
+            list: vec![number(1), number(2)],
+            negated: false,
+        });
+
+        match select_where("SELECT * FROM t WHERE NOT EXISTS (SELECT 1 FROM u) OR x > ALL (SELECT y FROM u)") {
+            ExpressionKind::BinaryOperation { left_operand, operator: BinaryOperator::Or, right_operand } => {
+                assert!(matches!(left_operand.kind, ExpressionKind::UnaryOperation { operator: UnaryOperator::Not, ref operand }
+                    if matches!(operand.kind, ExpressionKind::Exists(_))));
+                assert!(matches!(right_operand.kind, ExpressionKind::QuantifiedComparison {
+                    operator: BinaryOperator::GreaterThan, quantifier: Quantifier::All, .. }));
+            }
+            other => panic!("expected OR, got {:?}", other),
+        }
+
+        let columns = select("SELECT name, (SELECT COUNT(*) FROM orders o WHERE o.uid = u.id) AS n FROM users u").columns;
+        assert!(matches!(&columns[1],
+            SelectItem::Expression { expr, alias: Some(alias) } if alias == "n" && matches!(expr.kind, ExpressionKind::Subquery(_))));
+
+        let err = parse_statement("SELECT * FROM t WHERE a + ANY (SELECT 1 FROM u)").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("ANY needs a comparison operator before it".to_string()));
+        let err = parse_statement("SELECT * FROM t WHERE a IN (SELECT b FROM u").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("Expected ')'".to_string()));
+    }
+
+    #[test]
+    fn test_subqueries_in_nested_parentheses() {
+        let union = "(SELECT a FROM u) UNION (SELECT b FROM v)";
+        match select_where(&format!("SELECT * FROM t WHERE x IN ({})", union)) {
+            ExpressionKind::InSubquery { subquery, .. } => assert_eq!(subquery.to_string(), union),
+            other => panic!("expected IN subquery, got {:?}", other),
+        }
+        match select_where(&format!("SELECT * FROM t WHERE x = ({})", union)) {
+            ExpressionKind::BinaryOperation { right_operand, .. } => assert!(matches!(right_operand.kind, ExpressionKind::Subquery(_))),
+            other => panic!("expected =, got {:?}", other),
+        }
+
+        let statement = parse_statement(&format!("SELECT ({}), ((SELECT 1 FROM u) + 1) FROM t WHERE x IN ((SELECT a FROM u), 2)", union)).unwrap();
+        assert_eq!(statement.to_string(), format!("SELECT ({}), ((SELECT 1 FROM u) + 1) FROM t WHERE x IN ((SELECT a FROM u), 2)", union));
+        assert!(matches!(select_where("SELECT * FROM t WHERE x IN ((SELECT a FROM u), 2)"),
+            ExpressionKind::InList { ref list, .. } if list.len() == 2));
+    }
+
+    #[test]
+    fn test_derived_table() {
+        match &select("SELECT x.n FROM (SELECT COUNT(*) n FROM t) AS x JOIN u ON u.n = x.n").from[0] {
+            TableReference::Join { left, .. } => assert!(matches!(&**left,
+                TableReference::Derived { alias: Some(alias), .. } if alias == "x")),
+            other => panic!("expected JOIN, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        // INTERSECT binds tighter, UNION and EXCEPT group from the left.
+        let query = query("SELECT a FROM t UNION ALL SELECT a FROM u INTERSECT SELECT a FROM v EXCEPT SELECT a FROM w ORDER BY 1 LIMIT 5");
+        match &query.body {
+            SetExpr::SetOperation { left, operator: SetOperator::Except, quantifier: None, right } => {
+                assert!(matches!(&**right, SetExpr::Select(_)));
+                match &**left {
+                    SetExpr::SetOperation { operator: SetOperator::Union, quantifier: Some(SetQuantifier::All), right, .. } => {
+                        assert!(matches!(&**right, SetExpr::SetOperation { operator: SetOperator::Intersect, .. }));
+                    }
+                    other => panic!("expected UNION ALL, got {:?}", other),
+                }
+            }
+            other => panic!("expected EXCEPT, got {:?}", other),
+        }
+        // ORDER BY and LIMIT belong to the whole compound.
+        assert_eq!(query.order_by, vec![order(number(1), None, None)]);
+        assert_eq!(query.limit, Some(number(5)));
+
+        let query = self::query("(SELECT a FROM t ORDER BY a LIMIT 1) UNION DISTINCT (SELECT b FROM u)");
+        match &query.body {
+            SetExpr::SetOperation { left, quantifier: Some(SetQuantifier::Distinct), .. } => match &**left {
+                SetExpr::Query(inner) => assert_eq!(inner.limit, Some(number(1))),
+                other => panic!("expected a parenthesized query, got {:?}", other),
+            },
+            other => panic!("expected UNION DISTINCT, got {:?}", other),
+        }
+        assert!(query.order_by.is_empty());
+
+        assert!(parse_statement("SELECT * FROM t UNION SELECT a, b FROM u").is_ok());
+        assert!(parse_statement("INSERT INTO t SELECT a FROM u UNION SELECT a FROM v").is_ok());
+        assert!(parse_statement("SELECT * FROM t WHERE a IN (SELECT a FROM u EXCEPT SELECT a FROM v)").is_ok());
+        let err = parse_statement("SELECT a FROM t UNION SELECT a, b FROM u").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("each UNION query must have the same number of columns".to_string()));
+        assert_eq!(err.span.start.column, 17);
+        let err = parse_statement("SELECT a FROM t ORDER BY a UNION SELECT a FROM u").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("Unexpected token after end of statement".to_string()));
+    }
+
+    #[test]
+    fn test_with_clause() {
+        let query = query("WITH RECURSIVE r (n) AS MATERIALIZED (SELECT 1 FROM t), s AS (SELECT n FROM r) SELECT * FROM s");
+        let with = query.with.expect("a WITH clause");
+        assert!(with.recursive);
+        assert_eq!(with.ctes.len(), 2);
+        assert_eq!(with.ctes[0].name, "r");
+        assert_eq!(with.ctes[0].columns, vec!["n".to_string()]);
+        assert_eq!(with.ctes[0].materialized, Some(CteMaterialization::Materialized));
+        assert_eq!(with.ctes[1].materialized, None);
+        assert!(with.ctes[1].query.with.is_none());
+        assert_eq!(query.body.columns().len(), 1);
+        assert_eq!(query.span.start.column, 1);
+
+        for input in [
+            "WITH x AS (SELECT a FROM t) INSERT INTO u SELECT a FROM x",
+            "WITH x AS NOT MATERIALIZED (SELECT a FROM t) UPDATE u SET a = 1 WHERE a IN (SELECT a FROM x)",
+            "WITH x AS (SELECT a FROM t) DELETE FROM u WHERE EXISTS (SELECT 1 FROM x)",
+            "SELECT * FROM (WITH x AS (SELECT a FROM t) SELECT a FROM x) AS y",
+        ] {
+            assert!(parse_statement(input).is_ok(), "{}", input);
+        }
+
+        let err = parse_statement("WITH x AS (SELECT a FROM t), x AS (SELECT b FROM t) SELECT * FROM x").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::General("CTE x is defined more than once".to_string()));
+        assert_eq!(err.span.start.column, 30);
+
+        let err = parse_statement("WITH x AS (SELECT a FROM t) CREATE TABLE y (id INT)").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedKeyword("SELECT, INSERT, UPDATE or DELETE".to_string()));
+        assert!(parse_statement("WITH x (SELECT a FROM t) SELECT * FROM x").is_err());
+    }
+
+    #[test]
+    fn test_insert_values() {
+        let stmt = parse_statement("INSERT INTO users (id, name) VALUES (1, 'ann'), (2, 'bob');").unwrap();
+        match stmt {
+            Statement::Insert { table_name, columns, source, .. } => {
+                assert_eq!(table_name, "users");
+                assert_eq!(columns, vec!["id".to_string(), "name".to_string()]);
+                assert_eq!(source, InsertSource::Values(vec![
+                    vec![number(1), string("ann")],
+                    vec![number(2), string("bob")],
+                ]));
+            }
+            _ => panic!("Expected INSERT statement"),
+        }
+    }
+
+    #[test]
+    fn test_insert_select() {
+        let stmt = parse_statement("INSERT INTO archive SELECT * FROM users WHERE active = FALSE").unwrap();
+        match stmt {
+            Statement::Insert { columns, source: InsertSource::Query(query), .. } => {
+                assert!(columns.is_empty());
+                assert!(matches!(query.body, SetExpr::Select(ref select) if select.selection.is_some()));
+            }
+            _ => panic!("Expected INSERT ... SELECT"),
+        }
+
+        for input in ["INSERT INTO t (SELECT a FROM u)", "INSERT INTO t ((SELECT a FROM u) UNION (SELECT b FROM v))", "INSERT INTO t (x) (SELECT a FROM u)"] {
+            let stmt = parse_statement(input).unwrap();
+            assert!(matches!(stmt, Statement::Insert { source: InsertSource::Query(ref query), .. } if matches!(query.body, SetExpr::Query(_))));
+            assert_eq!(stmt.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_update_with_where() {
+        let stmt = parse_statement("UPDATE users SET age = age + 1, name = 'x' WHERE id = 3;").unwrap();
+        match stmt {
+            Statement::Update { table_name, assignments, selection, .. } => {
+                assert_eq!(table_name, "users");
+                assert_eq!(assignments, vec![
+                    Assignment { column: "age".to_string(), value: binary(ident("age"), BinaryOperator::Add, number(1)) },
+                    Assignment { column: "name".to_string(), value: string("x") },
+                ]);
+                assert_eq!(selection, Some(binary(ident("id"), BinaryOperator::Equals, number(3))));
+            }
+            _ => panic!("Expected UPDATE statement"),
+        }
+    }
+
+    #[test]
+    fn test_delete_with_where() {
+        let stmt = parse_statement("DELETE FROM users WHERE id = 3").unwrap();
+        assert_eq!(stmt, Statement::Delete {
+            with: None,
+            table_name: "users".to_string(),
+            selection: Some(binary(ident("id"), BinaryOperator::Equals, number(3))),
+            span: Span::default(),
+        });
+    }
+
+    #[test]
+    fn test_statement_rejects_trailing_tokens() {
+        let err = parse_statement("SELECT a FROM t WHERE x = 1 2").unwrap_err();
+        assert_eq!(err.found.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let tokens = Tokenizer::new("CREATE TABLE t (id INT);\n;INSERT INTO t VALUES (1);\nSELECT * FROM t;").tokenize().unwrap();
+        let statements = SQLParser::new(&tokens).parse_script().unwrap();
+
+        assert_eq!(statements.len(), 3);
+        assert!(matches!(statements[0], Statement::CreateTable { .. }));
+        assert!(matches!(statements[1], Statement::Insert { .. }));
+        assert!(matches!(statements[2], Statement::Query(_)));
+        assert_eq!(statements[2].span().start.line, 3);
+    }
+
+    #[test]
+    fn test_parse_script_reports_every_error() {
+        let input = "SELECT * FROM t;\nSELECT FROM t;\nDELETE FROM t;\nUPDATE t SET a = 1 2;\nSELECT 1 FROM";
+        let tokens = Tokenizer::new(input).tokenize().unwrap();
+        let errors = SQLParser::new(&tokens).parse_script().unwrap_err();
+
+        assert_eq!(errors.iter().map(|e| e.index).collect::<Vec<_>>(), vec![1, 3, 4]);
+        let second = errors[0].span;
+        assert_eq!(&input[second.start.offset..second.end.offset], "SELECT FROM t");
+        assert_eq!(errors[1].error.found.as_deref(), Some("2"));
+        assert_eq!(errors[2].error.found.as_deref(), Some("end of input"));
+    }
 }
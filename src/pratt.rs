@@ -1,121 +1,515 @@
 //Created by: Nisha Murali (Requested not to clone / re-use the code)
 //pratt.rs (Parses the expressions like a>b AND c<d(WHERE, ORDER))
-use crate::tokenizer::{Token, Keyword};
-use crate::ast::{Expression, BinaryOperator, UnaryOperator};
+use crate::tokenizer::{Token, Keyword, SpannedToken};
+use crate::ast::{Expression, ExpressionKind, FunctionArg, BinaryOperator, UnaryOperator, Quantifier, Query, CaseBranch,
+    IN_PRECEDENCE, IS_PRECEDENCE};
+use crate::dialect::{Dialect, GenericDialect};
+use crate::functions::{FunctionKind, FunctionRegistry};
+use crate::parser;
+use crate::span::Span;
+use crate::error::{ParseError, ParseErrorKind};
 
 pub struct PrattParser<'a> 
 {
-    pub tokens: &'a [Token],
+    pub tokens: &'a [SpannedToken],
     pub position: usize,
+    dialect: &'a dyn Dialect,
+    functions: &'a FunctionRegistry,
 }
 impl<'a> PrattParser<'a> 
 {
-    pub fn new(tokens: &'a [Token]) -> Self 
+    pub fn new(tokens: &'a [SpannedToken]) -> Self 
     {
-        Self { tokens, position: 0 }
+        Self { tokens, position: 0, dialect: &GenericDialect, functions: FunctionRegistry::builtins() }
+    }
+
+    /// The dialect subqueries are parsed with.
+    pub fn dialect(mut self, dialect: &'a dyn Dialect) -> Self 
+    {
+        self.dialect = dialect;
+        self
+    }
+
+    /// The functions calls are checked against.
+    pub fn functions(mut self, functions: &'a FunctionRegistry) -> Self 
+    {
+        self.functions = functions;
+        self
     }
 
     fn peek(&self) -> Option<&Token> 
     {
-        self.tokens.get(self.position)
+        self.tokens.get(self.position).map(|t| &t.token)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> 
+    {
+        self.tokens.get(self.position + n).map(|t| &t.token)
     }
 
     fn advance(&mut self) -> Option<&Token>
     {
-        let token = self.tokens.get(self.position);
+        let token = self.tokens.get(self.position).map(|t| &t.token);
         self.position += 1;
         token
     }
 
-    fn get_precedence(token: &Token) -> u8 
+    /// Span of the next token, or of the last one once the input is used up.
+    fn peek_span(&self) -> Span 
+    {
+        self.tokens.get(self.position)
+            .or(self.tokens.last())
+            .map(|t| t.span)
+            .unwrap_or_default()
+    }
+
+    /// Span of the most recently consumed token.
+    fn previous_span(&self) -> Span 
+    {
+        self.position.checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map(|t| t.span)
+            .unwrap_or_default()
+    }
+
+    /// An error pointing at the most recently consumed token.
+    fn error_at_previous(&self, kind: ParseErrorKind) -> ParseError 
+    {
+        match self.position.checked_sub(1).and_then(|i| self.tokens.get(i)) 
+        {
+            Some(token) => ParseError::at(kind, token),
+            None => ParseError::new(kind, self.peek_span()),
+        }
+    }
+
+    /// The binary operator `token` stands for, if any. Precedence lives on
+    /// `BinaryOperator` so the SQL printer can share it.
+    fn binary_operator(token: &Token) -> Option<BinaryOperator> 
     {
         match token 
         {
-            Token::Keyword(Keyword::Or) => 1,
-            Token::Keyword(Keyword::And) => 2,
-            Token::Equal | Token::NotEqual => 3,
-            Token::GreaterThan | Token::GreaterThanOrEqual |
-            Token::LessThan | Token::LessThanOrEqual => 4,
-            Token::Plus | Token::Minus => 5,
-            Token::Multiply | Token::Divide => 6,
-            _ => 0,
+            Token::Equal => Some(BinaryOperator::Equals),
+            Token::NotEqual => Some(BinaryOperator::NotEquals),
+            Token::GreaterThan => Some(BinaryOperator::GreaterThan),
+            Token::GreaterThanOrEqual => Some(BinaryOperator::GreaterThanOrEqual),
+            Token::LessThan => Some(BinaryOperator::LessThan),
+            Token::LessThanOrEqual => Some(BinaryOperator::LessThanOrEqual),
+            Token::Plus => Some(BinaryOperator::Add),
+            Token::Minus => Some(BinaryOperator::Subtract),
+            Token::Multiply => Some(BinaryOperator::Multiply),
+            Token::Divide => Some(BinaryOperator::Divide),
+            Token::Keyword(Keyword::And) => Some(BinaryOperator::And),
+            Token::Keyword(Keyword::Or) => Some(BinaryOperator::Or),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, expected: Token, help: &str) -> Result<(), ParseError> 
+    {
+        match self.advance() 
+        {
+            Some(t) if *t == expected => Ok(()),
+            _ => Err(self.error_at_previous(ParseErrorKind::InvalidExpression(format!("Expected '{}'", expected))).with_help(help)),
+        }
+    }
+
+    /// Hands the query starting at the next token back to the statement
+    /// parser and skips past it.
+    fn parse_subquery(&mut self) -> Result<Box<Query>, ParseError> 
+    {
+        let remaining = &self.tokens[self.position.min(self.tokens.len())..];
+        let (query, used) = parser::parse_subquery(remaining, self.dialect, self.functions)?;
+        self.position += used;
+        Ok(Box::new(query))
+    }
+
+    /// Whether a query starts `n` tokens ahead, possibly behind more `(`
+    /// as in `((SELECT a FROM u) UNION (SELECT b FROM v))`.
+    fn starts_query(&self, mut n: usize) -> bool 
+    {
+        while let Some(Token::LeftParentheses) = self.peek_nth(n) 
+        {
+            n += 1;
+        }
+        matches!(self.peek_nth(n), Some(Token::Keyword(Keyword::Select | Keyword::With)))
+    }
+
+    /// Parses the query inside a `(` that has just been consumed, leaving
+    /// the `)`. A query behind further parentheses is only tried, because
+    /// `((SELECT a FROM u) + 1)` is an expression: on failure nothing is consumed
+    /// and `None` tells the caller to parse an expression instead.
+    fn parse_query_in_parentheses(&mut self) -> Result<Option<Box<Query>>, ParseError> 
+    {
+        if let Some(Token::Keyword(Keyword::Select | Keyword::With)) = self.peek() 
+        {
+            return self.parse_subquery().map(Some);
+        }
+        if !self.starts_query(0) 
+        {
+            return Ok(None);
+        }
+        let position = self.position;
+        match self.parse_subquery() 
+        {
+            Ok(subquery) if matches!(self.peek(), Some(Token::RightParentheses)) => Ok(Some(subquery)),
+            _ => 
+            {
+                self.position = position;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Parses `(SELECT ...)`, the subquery of EXISTS, IN, ANY and ALL.
+    fn parse_parenthesized_subquery(&mut self) -> Result<Box<Query>, ParseError> 
+    {
+        self.expect(Token::LeftParentheses, "a subquery is written (SELECT ...)")?;
+        let subquery = self.parse_subquery()?;
+        self.expect(Token::RightParentheses, "every '(' needs a matching ')'")?;
+        Ok(subquery)
+    }
+
+    /// Parses the rest of `left [NOT] IN (...)` after the IN keyword.
+    fn parse_in(&mut self, left: Expression, negated: bool) -> Result<Expression, ParseError> 
+    {
+        self.expect(Token::LeftParentheses, "IN takes a list or a subquery in parentheses")?;
+        let start = left.span;
+        let kind = if let Some(subquery) = self.parse_query_in_parentheses()? 
+        {
+            ExpressionKind::InSubquery { expr: Box::new(left), subquery, negated }
+        } 
+        else 
+        {
+            let mut list = vec![self.parse_expression(1)?];
+            while let Some(Token::Comma) = self.peek() 
+            {
+                self.advance();
+                list.push(self.parse_expression(1)?);
+            }
+            ExpressionKind::InList { expr: Box::new(left), list, negated }
+        };
+        self.expect(Token::RightParentheses, "every '(' needs a matching ')'")?;
+        Ok(Expression::new(kind, start.union(self.previous_span())))
+    }
+
+    /// Parses the parenthesised arguments of a call to `name`, which started
+    /// at `start`, and an optional `FILTER (WHERE ...)`. Calls to functions
+    /// in the registry must match its argument counts.
+    fn parse_function_call(&mut self, name: String, start: Span) -> Result<ExpressionKind, ParseError> 
+    {
+        match self.advance() 
+        {
+            Some(Token::LeftParentheses) => {}
+            _ => return Err(self.error_at_previous(ParseErrorKind::InvalidExpression(format!("Expected '(' after {}", name)))),
+        }
+
+        let mut args = Vec::new();
+        let mut distinct = false;
+        if let Some(Token::RightParentheses) = self.peek() 
+        {
+            self.advance();
+        } 
+        else 
+        {
+            if let Some(Token::Keyword(Keyword::Distinct)) = self.peek() 
+            {
+                self.advance();
+                distinct = true;
+            }
+            loop 
+            {
+                if let Some(Token::Multiply) = self.peek() 
+                {
+                    self.advance();
+                    let plain_count = name.eq_ignore_ascii_case("count") && !distinct && args.is_empty()
+                        && matches!(self.peek(), Some(Token::RightParentheses));
+                    if !plain_count 
+                    {
+                        return Err(self.error_at_previous(ParseErrorKind::InvalidExpression(format!("'*' is not an argument of {}", name)))
+                            .with_help("'*' is only allowed in COUNT(*)"));
+                    }
+                    args.push(FunctionArg::Wildcard);
+                } 
+                else 
+                {
+                    args.push(FunctionArg::Expression(self.parse_expression(1)?));
+                }
+
+                match self.advance() 
+                {
+                    Some(Token::Comma) => continue,
+                    Some(Token::RightParentheses) => break,
+                    _ => return Err(self.error_at_previous(ParseErrorKind::InvalidExpression(format!("Expected ',' or ')' in call to {}", name)))),
+                }
+            }
+        }
+
+        // FILTER is not reserved, so it is only special right after a call.
+        let mut filter = None;
+        if let (Some(Token::Identifier(word)), Some(Token::LeftParentheses)) = (self.peek(), self.peek_nth(1)) 
+        {
+            if word.eq_ignore_ascii_case("filter") 
+            {
+                self.advance();
+                self.advance();
+                match self.advance() 
+                {
+                    Some(Token::Keyword(Keyword::Where)) => {}
+                    _ => return Err(self.error_at_previous(ParseErrorKind::ExpectedKeyword(Keyword::Where.to_string()))
+                        .with_help("a filter is written FILTER (WHERE condition)")),
+                }
+                filter = Some(Box::new(self.parse_expression(1)?));
+                self.expect(Token::RightParentheses, "every '(' needs a matching ')'")?;
+            }
+        }
+
+        let functions = self.functions;
+        let Some(signature) = functions.get(&name) else 
+        {
+            return Ok(ExpressionKind::Function { name, args, distinct, filter });
+        };
+        let span = start.union(self.previous_span());
+        if !signature.accepts(args.len()) 
+        {
+            let kind = ParseErrorKind::InvalidExpression(format!("{} takes {}, got {}", signature.name, signature.arity(), args.len()));
+            return Err(ParseError::new(kind, span));
+        }
+        if signature.kind == FunctionKind::Scalar && (distinct || filter.is_some()) 
+        {
+            let clause = if distinct { "DISTINCT" } else { "FILTER" };
+            let kind = ParseErrorKind::InvalidExpression(format!("{} is only allowed in aggregate calls", clause));
+            return Err(ParseError::new(kind, span).with_help(format!("{} is not an aggregate function", signature.name)));
+        }
+        Ok(ExpressionKind::Function { name: signature.name.clone(), args, distinct, filter })
+    }
+
+    /// Parses the rest of `CASE [operand] WHEN ... THEN ... [ELSE ...] END`
+    /// after the CASE keyword.
+    fn parse_case(&mut self) -> Result<ExpressionKind, ParseError> 
+    {
+        let mut operand = None;
+        if !matches!(self.peek(), Some(Token::Keyword(Keyword::When))) 
+        {
+            operand = Some(Box::new(self.parse_expression(1)?));
+        }
+
+        let mut branches = Vec::new();
+        while let Some(Token::Keyword(Keyword::When)) = self.peek() 
+        {
+            self.advance();
+            let condition = self.parse_expression(1)?;
+            self.expect(Token::Keyword(Keyword::Then), "each WHEN is followed by THEN and its result")?;
+            let result = self.parse_expression(1)?;
+            branches.push(CaseBranch { condition, result });
+        }
+        if branches.is_empty() 
+        {
+            self.advance();
+            return Err(self.error_at_previous(ParseErrorKind::InvalidExpression("Expected 'WHEN'".to_string()))
+                .with_help("CASE needs at least one WHEN ... THEN ..."));
         }
+
+        let mut else_result = None;
+        if let Some(Token::Keyword(Keyword::Else)) = self.peek() 
+        {
+            self.advance();
+            else_result = Some(Box::new(self.parse_expression(1)?));
+        }
+        self.expect(Token::Keyword(Keyword::End), "every CASE needs a matching END")?;
+        Ok(ExpressionKind::Case { operand, branches, else_result })
     }
 
-    pub fn parse_expression(&mut self, min_precedence: u8) -> Result<Expression, String> 
+    /// Rejects the operator at the next token, which binds tighter than the
+    /// `postfix` test on its left. `a IS NULL = b` would be read as
+    /// `(a IS NULL) = b`, but prints back with parentheses that the parser
+    /// keeps as a separate node, so they have to be written.
+    fn operator_after_postfix(&mut self, postfix: &str) -> ParseError 
     {
-        let mut left = match self.advance() 
+        let operator = self.peek().map(|t| t.to_string()).unwrap_or_default();
+        self.advance();
+        let kind = ParseErrorKind::InvalidExpression(format!("{} cannot follow {} without parentheses", operator, postfix));
+        self.error_at_previous(kind).with_help(format!("put the {} test in parentheses", postfix))
+    }
+
+    pub fn parse_expression(&mut self, min_precedence: u8) -> Result<Expression, ParseError> 
+    {
+        let start = self.peek_span();
+        let kind = match self.advance() 
         {
-            Some(Token::Identifier(name)) => Expression::Identifier(name.clone()),
-            Some(Token::Number(n)) => Expression::Number(*n),
-            Some(Token::String(s)) => Expression::String(s.clone()),
-            Some(Token::Keyword(Keyword::True)) => Expression::Boolean(true),
-            Some(Token::Keyword(Keyword::False)) => Expression::Boolean(false),
+            Some(Token::Identifier(name) | Token::QuotedIdentifier(name)) => 
+            {
+                let mut parts = vec![name.clone()];
+                while let Some(Token::Period) = self.peek() 
+                {
+                    self.advance();
+                    match self.advance() 
+                    {
+                        Some(Token::Identifier(part) | Token::QuotedIdentifier(part)) => parts.push(part.clone()),
+                        _ => return Err(self.error_at_previous(ParseErrorKind::ExpectedIdentifier)),
+                    }
+                }
+                // Any name followed by `(` is a call, including aggregate
+                // names a dialect leaves unreserved and `schema.function(...)`.
+                if let Some(Token::LeftParentheses) = self.peek() 
+                {
+                    self.parse_function_call(parts.join("."), start)?
+                } 
+                else if parts.len() == 1 
+                {
+                    ExpressionKind::Identifier(parts.remove(0))
+                } 
+                else 
+                {
+                    ExpressionKind::CompoundIdentifier(parts)
+                }
+            }
+            Some(Token::Number(n)) => ExpressionKind::Number(n.clone()),
+            Some(Token::String(s)) => ExpressionKind::String(s.clone()),
+            Some(Token::Keyword(Keyword::True)) => ExpressionKind::Boolean(true),
+            Some(Token::Keyword(Keyword::False)) => ExpressionKind::Boolean(false),
+            Some(Token::Keyword(Keyword::Null)) => ExpressionKind::Null,
+            Some(Token::Placeholder(p)) => ExpressionKind::Placeholder(p.clone()),
             Some(Token::Keyword(Keyword::Not)) => 
             {
-                let expr = self.parse_expression(6)?;
-                Expression::UnaryOperation {
+                // NOT binds looser than comparisons: NOT a = 1 is NOT (a = 1)
+                let expr = self.parse_expression(UnaryOperator::Not.precedence())?;
+                ExpressionKind::UnaryOperation {
                     operator: UnaryOperator::Not,
                     operand: Box::new(expr),
                 }
             }
             Some(Token::Minus) => 
             {
-                let expr = self.parse_expression(6)?;
-                Expression::UnaryOperation 
+                let expr = self.parse_expression(UnaryOperator::Negate.precedence())?;
+                ExpressionKind::UnaryOperation 
                 {
                     operator: UnaryOperator::Negate,
                     operand: Box::new(expr),
                 }
             }
+            Some(Token::Keyword(Keyword::Exists)) => ExpressionKind::Exists(self.parse_parenthesized_subquery()?),
+            Some(Token::Keyword(Keyword::Case)) => self.parse_case()?,
             Some(Token::LeftParentheses) => 
             {
-                let expr = self.parse_expression(1)?;
-                match self.advance() 
+                if let Some(subquery) = self.parse_query_in_parentheses()? 
+                {
+                    self.expect(Token::RightParentheses, "every '(' needs a matching ')'")?;
+                    ExpressionKind::Subquery(subquery)
+                } 
+                else 
                 {
-                    Some(Token::RightParentheses) => Expression::Grouped(Box::new(expr)),
-                    _ => return Err("Expected ')'".to_string()),
+                    let expr = self.parse_expression(1)?;
+                    match self.advance() 
+                    {
+                        Some(Token::RightParentheses) => ExpressionKind::Grouped(Box::new(expr)),
+                        _ => return Err(self.error_at_previous(ParseErrorKind::InvalidExpression("Expected ')'".to_string()))
+                            .with_help("every '(' needs a matching ')'")),
+                    }
                 }
             }
-            Some(t) => return Err(format!("Unexpected token: {:?}", t)),
-            None => return Err("Unexpected end of input".to_string()),
+            Some(t) => 
+            {
+                let kind = ParseErrorKind::InvalidExpression(format!("Unexpected token: {}", t));
+                return Err(self.error_at_previous(kind));
+            }
+            None => return Err(ParseError::new(ParseErrorKind::UnexpectedEnd, self.peek_span())),
         };
+        let mut left = Expression::new(kind, start.union(self.previous_span()));
 
+        // The postfix test `left` ends with, and how tightly it binds.
+        let mut postfix: Option<(&str, u8)> = None;
         loop {
-            let op = match self.peek() 
+            let negated = matches!((self.peek(), self.peek_nth(1)), (Some(Token::Keyword(Keyword::Not)), Some(Token::Keyword(Keyword::In))));
+            if (negated || matches!(self.peek(), Some(Token::Keyword(Keyword::In)))) && IN_PRECEDENCE >= min_precedence 
+            {
+                if let Some((name, precedence)) = postfix 
+                {
+                    if IN_PRECEDENCE > precedence 
+                    {
+                        return Err(self.operator_after_postfix(name));
+                    }
+                }
+                if negated 
+                {
+                    self.advance();
+                }
+                self.advance();
+                left = self.parse_in(left, negated)?;
+                postfix = Some(("IN", IN_PRECEDENCE));
+                continue;
+            }
+
+            if matches!(self.peek(), Some(Token::Keyword(Keyword::Is))) && IS_PRECEDENCE >= min_precedence 
             {
-                Some(tok) if Self::get_precedence(tok) >= min_precedence => tok.clone(),
+                self.advance();
+                let negated = matches!(self.peek(), Some(Token::Keyword(Keyword::Not)));
+                if negated 
+                {
+                    self.advance();
+                }
+                match self.advance() 
+                {
+                    Some(Token::Keyword(Keyword::Null)) => {}
+                    _ => return Err(self.error_at_previous(ParseErrorKind::InvalidExpression("Expected NULL after IS".to_string()))
+                        .with_help("write IS NULL or IS NOT NULL")),
+                }
+                let span = left.span.union(self.previous_span());
+                left = Expression::new(ExpressionKind::IsNull { expr: Box::new(left), negated }, span);
+                postfix = Some(("IS NULL", IS_PRECEDENCE));
+                continue;
+            }
+
+            let operator = match self.peek().and_then(Self::binary_operator) 
+            {
+                Some(op) if op.precedence() >= min_precedence => op,
                 _ => break,
             };
-
-            let precedence = Self::get_precedence(&op);
+            if let Some((name, precedence)) = postfix 
+            {
+                if operator.precedence() > precedence 
+                {
+                    return Err(self.operator_after_postfix(name));
+                }
+            }
+            postfix = None;
             self.advance();
 
-            let right = self.parse_expression(precedence + 1)?;
-
-            let operator = match op 
-            {
-                Token::Equal => BinaryOperator::Equals,
-                Token::NotEqual => BinaryOperator::NotEquals,
-                Token::GreaterThan => BinaryOperator::GreaterThan,
-                Token::GreaterThanOrEqual => BinaryOperator::GreaterThanOrEqual,
-                Token::LessThan => BinaryOperator::LessThan,
-                Token::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
-                Token::Plus => BinaryOperator::Add,
-                Token::Minus => BinaryOperator::Subtract,
-                Token::Multiply => BinaryOperator::Multiply,
-                Token::Divide => BinaryOperator::Divide,
-                Token::Keyword(Keyword::And) => BinaryOperator::And,
-                Token::Keyword(Keyword::Or) => BinaryOperator::Or,
-                _ => return Err(format!("Unknown operator {:?}", op)),
+            let quantifier = match self.peek() 
+            {
+                Some(Token::Keyword(Keyword::Any)) => Some(Quantifier::Any),
+                Some(Token::Keyword(Keyword::All)) => Some(Quantifier::All),
+                _ => None,
             };
+            if let Some(quantifier) = quantifier 
+            {
+                if !operator.is_comparison() 
+                {
+                    self.advance();
+                    let kind = ParseErrorKind::InvalidExpression(format!("{} needs a comparison operator before it", quantifier));
+                    return Err(self.error_at_previous(kind));
+                }
+                self.advance();
+                let subquery = self.parse_parenthesized_subquery()?;
+                let span = left.span.union(self.previous_span());
+                left = Expression::new(ExpressionKind::QuantifiedComparison 
+                {
+                    left_operand: Box::new(left),
+                    operator,
+                    quantifier,
+                    subquery,
+                }, span);
+                continue;
+            }
+
+            let right = self.parse_expression(operator.precedence() + 1)?;
 
-            left = Expression::BinaryOperation 
+            let span = left.span.union(right.span);
+            left = Expression::new(ExpressionKind::BinaryOperation 
             {
                 left_operand: Box::new(left),
                 operator,
                 right_operand: Box::new(right),
-            };
+            }, span);
         }
 
         Ok(left)
@@ -125,27 +519,205 @@ impl<'a> PrattParser<'a>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tokenizer::{Tokenizer, Token};
+    use crate::tokenizer::Tokenizer;
+    use crate::test_helpers::{parse_expression, ident, binary};
 
     #[test]
     fn test_simple_expression() {
-        let tokens = {
-            let mut tokenizer = Tokenizer::new("age >= 18");
-            let mut tokens = Vec::new();
-            loop {
-                let tok = tokenizer.next_token();
-                tokens.push(tok.clone());
-                if tok == Token::Eof {
-                    break;
+        let tokens = Tokenizer::new("age >= 18").tokenize().unwrap();
+
+        let mut parser = PrattParser::new(&tokens);
+        let expr = parser.parse_expression(1).unwrap();
+        match expr.kind {
+            ExpressionKind::BinaryOperation { .. } => {} // success
+            _ => panic!("Expected binary operation"),
+        }
+        assert_eq!((expr.span.start.offset, expr.span.end.offset), (0, 9));
+    }
+
+    #[test]
+    fn test_aggregate_calls() {
+        let tokens = Tokenizer::new("COUNT(*) > 1 AND SUM(DISTINCT price) >= GREATEST(a, b)").tokenize().unwrap();
+
+        let expr = PrattParser::new(&tokens).parse_expression(1).unwrap();
+        let (left, right) = match expr.kind {
+            ExpressionKind::BinaryOperation { left_operand, right_operand, .. } => (left_operand, right_operand),
+            _ => panic!("Expected binary operation"),
+        };
+        match left.kind {
+            ExpressionKind::BinaryOperation { left_operand, .. } => match left_operand.kind {
+                ExpressionKind::Function { name, args, distinct, .. } => {
+                    assert_eq!(name, "COUNT");
+                    assert_eq!(args, vec![FunctionArg::Wildcard]);
+                    assert!(!distinct);
                 }
+                _ => panic!("Expected COUNT(*)"),
+            },
+            _ => panic!("Expected comparison"),
+        }
+        match right.kind {
+            ExpressionKind::BinaryOperation { left_operand, right_operand, .. } => {
+                assert!(matches!(left_operand.kind, ExpressionKind::Function { ref name, distinct: true, .. } if name == "SUM"));
+                assert!(matches!(right_operand.kind, ExpressionKind::Function { ref args, .. } if args.len() == 2));
             }
-            tokens
-        };
+            _ => panic!("Expected comparison"),
+        }
+    }
+
+    #[test]
+    fn test_function_calls() {
+        match parse_expression("coalesce(lower(a), b, 'x')").unwrap().kind {
+            ExpressionKind::Function { name, args, .. } => {
+                assert_eq!(name, "COALESCE");
+                assert_eq!(args.len(), 3);
+                assert!(matches!(&args[0], FunctionArg::Expression(e)
+                    if matches!(&e.kind, ExpressionKind::Function { name, .. } if name == "LOWER")));
+            }
+            other => panic!("expected COALESCE call, got {:?}", other),
+        }
+        assert!(matches!(parse_expression("now()").unwrap().kind, ExpressionKind::Function { ref args, .. } if args.is_empty()));
+        // Unknown functions keep their spelling and take any arguments.
+        assert!(matches!(parse_expression("app.my_udf(1, 2, 3)").unwrap().kind, ExpressionKind::Function { ref name, .. } if name == "app.my_udf"));
+
+        match parse_expression("COUNT(*) FILTER (WHERE status = 'paid')").unwrap().kind {
+            ExpressionKind::Function { filter: Some(filter), .. } => assert!(matches!(filter.kind, ExpressionKind::BinaryOperation { .. })),
+            other => panic!("expected a filtered COUNT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_function_arity_errors() {
+        let err = parse_expression("x = LOWER(a, b)").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("LOWER takes exactly 1 argument, got 2".to_string()));
+        assert_eq!((err.span.start.column, err.span.end.column), (5, 16));
+
+        let err = parse_expression("date_trunc('month')").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("DATE_TRUNC takes exactly 2 arguments, got 1".to_string()));
+        let err = parse_expression("COALESCE()").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("COALESCE takes at least 1 argument, got 0".to_string()));
+        let err = parse_expression("UPPER(DISTINCT a)").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("DISTINCT is only allowed in aggregate calls".to_string()));
+        assert!(parse_expression("SUM(a) FILTER (a > 1)").is_err());
+
+        // '*' only counts rows.
+        let err = parse_expression("LOWER(*)").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("'*' is not an argument of LOWER".to_string()));
+        assert_eq!(err.span.start.column, 7);
+        assert!(parse_expression("COUNT(DISTINCT *)").is_err());
+        assert!(parse_expression("COUNT(a, *)").is_err());
+        assert!(parse_expression("my_udf(*)").is_err());
+
+        // A custom registry replaces the built-in one.
+        let mut functions = FunctionRegistry::new();
+        functions.register(crate::functions::FunctionSignature::scalar("lower", 1, Some(2)));
+        let tokens = Tokenizer::new("LOWER(a, 'tr_TR')").tokenize().unwrap();
+        assert!(PrattParser::new(&tokens).functions(&functions).parse_expression(1).is_ok());
+    }
+
+    #[test]
+    fn test_case_expressions() {
+        match parse_expression("CASE WHEN age < 18 THEN 'minor' WHEN age < 65 THEN 'adult' ELSE 'senior' END").unwrap().kind {
+            ExpressionKind::Case { operand: None, branches, else_result: Some(else_result) } => {
+                assert_eq!(branches.len(), 2);
+                assert!(matches!(branches[0].condition.kind, ExpressionKind::BinaryOperation { operator: BinaryOperator::LessThan, .. }));
+                assert_eq!(else_result.kind, ExpressionKind::String("senior".to_string()));
+            }
+            other => panic!("expected searched CASE, got {:?}", other),
+        }
+
+        // A simple CASE nested in a THEN, inside arithmetic.
+        let input = "1 + CASE status WHEN 'a' THEN CASE WHEN x IS NULL THEN 0 END ELSE 2 END * 3";
+        let expr = parse_expression(input).unwrap();
+        assert_eq!(expr.span.end.offset, input.len());
+        match expr.kind {
+            ExpressionKind::BinaryOperation { operator: BinaryOperator::Add, right_operand, .. } => match right_operand.kind {
+                ExpressionKind::BinaryOperation { left_operand, operator: BinaryOperator::Multiply, .. } => match left_operand.kind {
+                    ExpressionKind::Case { operand: Some(operand), branches, .. } => {
+                        assert_eq!(operand.kind, ExpressionKind::Identifier("status".to_string()));
+                        assert!(matches!(branches[0].result.kind, ExpressionKind::Case { operand: None, else_result: None, .. }));
+                    }
+                    other => panic!("expected simple CASE, got {:?}", other),
+                },
+                other => panic!("expected multiplication, got {:?}", other),
+            },
+            other => panic!("expected addition, got {:?}", other),
+        }
+
+        let err = parse_expression("CASE WHEN a THEN 1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("Expected 'END'".to_string()));
+        let err = parse_expression("CASE a ELSE 1 END").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("Expected 'WHEN'".to_string()));
+        let err = parse_expression("CASE WHEN a 1 END").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("Expected 'THEN'".to_string()));
+    }
+
+    #[test]
+    fn test_is_null_precedence() {
+        // IS applies to the whole comparison or sum on its left and binds
+        // tighter than NOT and AND.
+        match parse_expression("NOT a + 1 IS NOT NULL AND b").unwrap().kind {
+            ExpressionKind::BinaryOperation { left_operand, operator: BinaryOperator::And, .. } => match left_operand.kind {
+                ExpressionKind::UnaryOperation { operator: UnaryOperator::Not, operand } => assert!(matches!(operand.kind,
+                    ExpressionKind::IsNull { ref expr, negated: true } if matches!(expr.kind, ExpressionKind::BinaryOperation { .. }))),
+                other => panic!("expected NOT, got {:?}", other),
+            },
+            other => panic!("expected AND, got {:?}", other),
+        }
+        let err = parse_expression("a IS 1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("Expected NULL after IS".to_string()));
+
+        let err = parse_expression("a IS NULL = b").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("= cannot follow IS NULL without parentheses".to_string()));
+        assert_eq!(err.span.start.column, 11);
+        assert!(parse_expression("(a IS NULL) = b").is_ok());
+        assert!(parse_expression("a IS NULL AND b IS NOT NULL OR c").is_ok());
+        let err = parse_expression("a IS NULL IN (TRUE)").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("IN cannot follow IS NULL without parentheses".to_string()));
+
+        // The same goes for IN, which binds like `<`.
+        let err = parse_expression("a IN (1) + 1").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("+ cannot follow IN without parentheses".to_string()));
+        assert!(parse_expression("(a IN (1)) + 1").is_ok());
+        assert!(parse_expression("a IN (1) = b IN (2)").is_ok());
+
+        // ... and looser than every comparison.
+        for operator in [BinaryOperator::Equals, BinaryOperator::LessThan] {
+            let input = format!("a {} b IS NULL", operator);
+            let expected = ExpressionKind::IsNull { expr: Box::new(binary(ident("a"), operator, ident("b"))), negated: false };
+            assert_eq!(parse_expression(&input).unwrap().kind, expected);
+        }
+    }
+
+    #[test]
+    fn test_nullif_and_coalesce_expand_to_case() {
+        let case = |input: &str| parse_expression(input).unwrap().to_case().map(|e| e.to_string());
+        assert_eq!(case("NULLIF(a, '')").as_deref(), Some("CASE WHEN a = '' THEN NULL ELSE a END"));
+        assert_eq!(case("coalesce(a, b + 1, 0)").as_deref(),
+            Some("CASE WHEN a IS NOT NULL THEN a WHEN b + 1 IS NOT NULL THEN b + 1 ELSE 0 END"));
+        assert_eq!(case("COALESCE(a)").as_deref(), Some("CASE WHEN a IS NOT NULL THEN a END"));
+        assert_eq!(case("LOWER(a)"), None);
+    }
+
+    #[test]
+    fn test_unclosed_parenthesis_error() {
+        let tokens = Tokenizer::new("(a + 1").tokenize().unwrap();
+
+        let err = PrattParser::new(&tokens).parse_expression(1).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidExpression("Expected ')'".to_string()));
+        assert_eq!(err.found.as_deref(), Some("end of input"));
+        assert_eq!(err.span.start.column, 7);
+    }
+
+    #[test]
+    fn test_grouped_expression_span() {
+        let tokens = Tokenizer::new("x = (a + 1)").tokenize().unwrap();
 
         let mut parser = PrattParser::new(&tokens);
         let expr = parser.parse_expression(1).unwrap();
-        match expr {
-            Expression::BinaryOperation { .. } => {} // success
+        match expr.kind {
+            ExpressionKind::BinaryOperation { right_operand, .. } => {
+                assert_eq!((right_operand.span.start.column, right_operand.span.end.column), (5, 12));
+            }
             _ => panic!("Expected binary operation"),
         }
     }
@@ -0,0 +1,78 @@
+//Created by: Nisha Murali (Requested not to clone / re-use the code)
+//span.rs (Source locations for tokens and AST nodes)
+
+/// A single point in the source text. `offset` is a byte offset, `line` and
+/// `column` are 1-based and count characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location
+{
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Location
+{
+    fn default() -> Self
+    {
+        Self { offset: 0, line: 1, column: 1 }
+    }
+}
+
+/// The half-open range `start..end` of source text covered by a token or node.
+///
+/// Two spans are equal when they cover the same text. AST nodes leave their
+/// spans out of equality, see `ast.rs`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span
+{
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Span
+{
+    pub fn new(start: Location, end: Location) -> Self
+    {
+        Self { start, end }
+    }
+
+    /// Smallest span covering both `self` and `other`.
+    pub fn union(self, other: Span) -> Span
+    {
+        Span
+        {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.end.offset.saturating_sub(self.start.offset)
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_covers_both() {
+        let a = Span::new(Location { offset: 4, line: 1, column: 5 }, Location { offset: 7, line: 1, column: 8 });
+        let b = Span::new(Location { offset: 0, line: 1, column: 1 }, Location { offset: 2, line: 1, column: 3 });
+        let u = a.union(b);
+        assert_eq!(u.start.offset, 0);
+        assert_eq!(u.end.offset, 7);
+        assert_eq!(u.len(), 7);
+        assert_eq!(u, Span::new(b.start, a.end));
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,57 @@
+//Created by: Nisha Murali (Requested not to clone / re-use the code)
+//test_helpers.rs (Parsing shortcuts and AST builders shared by the unit tests)
+use crate::ast::{Statement, Expression, ExpressionKind, BinaryOperator, TableReference};
+use crate::error::ParseError;
+use crate::parser::SQLParser;
+use crate::pratt::PrattParser;
+use crate::span::Span;
+use crate::tokenizer::Tokenizer;
+
+/// Parses a single statement with the generic dialect.
+pub fn parse_statement(input: &str) -> Result<Statement, ParseError>
+{
+    let tokens = Tokenizer::new(input).tokenize()?;
+    SQLParser::new(&tokens).parse_statement()
+}
+
+/// Parses a single expression with the generic dialect.
+pub fn parse_expression(input: &str) -> Result<Expression, ParseError>
+{
+    let tokens = Tokenizer::new(input).tokenize()?;
+    PrattParser::new(&tokens).parse_expression(1)
+}
+
+pub fn ident(name: &str) -> Expression
+{
+    Expression::new(ExpressionKind::Identifier(name.to_string()), Span::default())
+}
+
+pub fn number(n: u64) -> Expression
+{
+    Expression::new(ExpressionKind::Number(n.to_string()), Span::default())
+}
+
+pub fn string(s: &str) -> Expression
+{
+    Expression::new(ExpressionKind::String(s.to_string()), Span::default())
+}
+
+pub fn placeholder(p: &str) -> Expression
+{
+    Expression::new(ExpressionKind::Placeholder(p.to_string()), Span::default())
+}
+
+pub fn binary(left: Expression, operator: BinaryOperator, right: Expression) -> Expression
+{
+    Expression::new(ExpressionKind::BinaryOperation
+    {
+        left_operand: Box::new(left),
+        operator,
+        right_operand: Box::new(right),
+    }, Span::default())
+}
+
+pub fn table(name: &str, alias: Option<&str>) -> TableReference
+{
+    TableReference::Table { name: name.to_string(), alias: alias.map(str::to_string) }
+}
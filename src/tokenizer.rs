@@ -1,5 +1,9 @@
 //Created by: Nisha Murali (Requested not to clone / re-use the code)
 //tokenizer.rs (Breaks the text into tokens)
+use crate::span::{Location, Span};
+use crate::error::{ParseError, ParseErrorKind};
+use crate::dialect::{Dialect, GenericDialect};
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Keyword 
 {
@@ -22,6 +26,55 @@ pub enum Keyword
     Or,
     True,
     False,
+    As,
+    Join,
+    Inner,
+    Left,
+    Right,
+    Full,
+    Outer,
+    Cross,
+    Natural,
+    On,
+    Using,
+    Group,
+    Having,
+    Distinct,
+    Insert,
+    Into,
+    Values,
+    Update,
+    Set,
+    Delete,
+    Asc,
+    Desc,
+    Nulls,
+    First,
+    Last,
+    Limit,
+    Offset,
+    Fetch,
+    Next,
+    Row,
+    Rows,
+    Only,
+    Top,
+    Exists,
+    In,
+    Any,
+    All,
+    With,
+    Recursive,
+    Materialized,
+    Union,
+    Intersect,
+    Except,
+    Case,
+    When,
+    Then,
+    Else,
+    End,
+    Is,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -29,8 +82,15 @@ pub enum Token
 {
     Keyword(Keyword),
     Identifier(String),
+    /// An identifier written in quotes, e.g. `"Mixed Case"`. It is never
+    /// read as a keyword, whatever its spelling.
+    QuotedIdentifier(String),
     String(String),
-    Number(u64),
+    /// A numeric literal exactly as written, e.g. `42`, `3.14`, `.5` or
+    /// `1e-9`. Kept as text so no precision is lost.
+    Number(String),
+    /// A bind parameter as written: `?`, `$1` or `:name`.
+    Placeholder(String),
     Invalid(char),
     LeftParentheses,
     RightParentheses,
@@ -45,17 +105,160 @@ pub enum Token
     Minus,
     Plus,
     Comma,
+    Period,
     Semicolon,
+    /// A `-- line` or `/* block */` comment, including its delimiters. Only
+    /// produced when the tokenizer is asked to keep comments.
+    Comment(String),
     Eof,
 }
 
-pub struct Tokenizer 
+/// How each keyword is spelled.
+const KEYWORDS: &[(&str, Keyword)] = &[
+    ("SELECT", Keyword::Select),
+    ("FROM", Keyword::From),
+    ("WHERE", Keyword::Where),
+    ("ORDER", Keyword::Order),
+    ("BY", Keyword::By),
+    ("CREATE", Keyword::Create),
+    ("TABLE", Keyword::Table),
+    ("INT", Keyword::Int),
+    ("BOOL", Keyword::Bool),
+    ("VARCHAR", Keyword::Varchar),
+    ("NOT", Keyword::Not),
+    ("NULL", Keyword::Null),
+    ("PRIMARY", Keyword::Primary),
+    ("KEY", Keyword::Key),
+    ("CHECK", Keyword::Check),
+    ("AND", Keyword::And),
+    ("OR", Keyword::Or),
+    ("TRUE", Keyword::True),
+    ("FALSE", Keyword::False),
+    ("AS", Keyword::As),
+    ("JOIN", Keyword::Join),
+    ("INNER", Keyword::Inner),
+    ("LEFT", Keyword::Left),
+    ("RIGHT", Keyword::Right),
+    ("FULL", Keyword::Full),
+    ("OUTER", Keyword::Outer),
+    ("CROSS", Keyword::Cross),
+    ("NATURAL", Keyword::Natural),
+    ("ON", Keyword::On),
+    ("USING", Keyword::Using),
+    ("GROUP", Keyword::Group),
+    ("HAVING", Keyword::Having),
+    ("DISTINCT", Keyword::Distinct),
+    ("INSERT", Keyword::Insert),
+    ("INTO", Keyword::Into),
+    ("VALUES", Keyword::Values),
+    ("UPDATE", Keyword::Update),
+    ("SET", Keyword::Set),
+    ("DELETE", Keyword::Delete),
+    ("ASC", Keyword::Asc),
+    ("DESC", Keyword::Desc),
+    ("NULLS", Keyword::Nulls),
+    ("FIRST", Keyword::First),
+    ("LAST", Keyword::Last),
+    ("LIMIT", Keyword::Limit),
+    ("OFFSET", Keyword::Offset),
+    ("FETCH", Keyword::Fetch),
+    ("NEXT", Keyword::Next),
+    ("ROW", Keyword::Row),
+    ("ROWS", Keyword::Rows),
+    ("ONLY", Keyword::Only),
+    ("TOP", Keyword::Top),
+    ("EXISTS", Keyword::Exists),
+    ("IN", Keyword::In),
+    ("ANY", Keyword::Any),
+    ("ALL", Keyword::All),
+    ("WITH", Keyword::With),
+    ("RECURSIVE", Keyword::Recursive),
+    ("MATERIALIZED", Keyword::Materialized),
+    ("UNION", Keyword::Union),
+    ("INTERSECT", Keyword::Intersect),
+    ("EXCEPT", Keyword::Except),
+    ("CASE", Keyword::Case),
+    ("WHEN", Keyword::When),
+    ("THEN", Keyword::Then),
+    ("ELSE", Keyword::Else),
+    ("END", Keyword::End),
+    ("IS", Keyword::Is),
+];
+
+impl Keyword 
+{
+    /// Whether the keyword only means something at one spot of the grammar,
+    /// like FIRST in `NULLS FIRST` or ROWS in `FETCH FIRST 5 ROWS ONLY`. The
+    /// tokenizer reads these as identifiers so they can still name columns,
+    /// and the parser matches unquoted ones there by spelling.
+    pub fn is_contextual(&self) -> bool 
+    {
+        matches!(self, Keyword::Nulls | Keyword::First | Keyword::Last | Keyword::Next | Keyword::Row | Keyword::Rows
+            | Keyword::Only | Keyword::Top)
+    }
+}
+
+impl std::fmt::Display for Keyword 
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+    {
+        write!(f, "{}", format!("{:?}", self).to_uppercase())
+    }
+}
+
+impl std::fmt::Display for Token 
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+    {
+        match self 
+        {
+            Token::Keyword(k) => write!(f, "{}", k),
+            Token::Identifier(name) => write!(f, "{}", name),
+            Token::QuotedIdentifier(name) => write!(f, "\"{}\"", name),
+            Token::String(s) => write!(f, "'{}'", s),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Placeholder(p) => write!(f, "{}", p),
+            Token::Invalid(ch) => write!(f, "{}", ch),
+            Token::LeftParentheses => write!(f, "("),
+            Token::RightParentheses => write!(f, ")"),
+            Token::GreaterThan => write!(f, ">"),
+            Token::GreaterThanOrEqual => write!(f, ">="),
+            Token::LessThan => write!(f, "<"),
+            Token::LessThanOrEqual => write!(f, "<="),
+            Token::Equal => write!(f, "="),
+            Token::NotEqual => write!(f, "!="),
+            Token::Multiply => write!(f, "*"),
+            Token::Divide => write!(f, "/"),
+            Token::Minus => write!(f, "-"),
+            Token::Plus => write!(f, "+"),
+            Token::Comma => write!(f, ","),
+            Token::Period => write!(f, "."),
+            Token::Semicolon => write!(f, ";"),
+            Token::Comment(text) => write!(f, "{}", text),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+/// A token together with the source text it was read from.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken 
+{
+    pub token: Token,
+    pub span: Span,
+}
+
+pub struct Tokenizer<'a> 
 {
     input: Vec<char>,
     position: usize,
+    location: Location,
+    token_start: Location,
+    keep_comments: bool,
+    dialect: &'a dyn Dialect,
 }
 
-impl Tokenizer 
+impl<'a> Tokenizer<'a> 
 {
     pub fn new(input: &str) -> Self 
     {
@@ -63,9 +266,29 @@ impl Tokenizer
         {
             input: input.chars().collect(),
             position: 0,
+            location: Location::default(),
+            token_start: Location::default(),
+            keep_comments: false,
+            dialect: &GenericDialect,
         }
     }
 
+    /// Read quotes, keywords and operators the way `dialect` does.
+    pub fn dialect(mut self, dialect: &'a dyn Dialect) -> Self 
+    {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Emit comments as `Token::Comment` trivia instead of dropping them.
+    /// The parser does not accept comment tokens, so only use this for
+    /// tooling that works on the token stream itself.
+    pub fn keep_comments(mut self, keep: bool) -> Self 
+    {
+        self.keep_comments = keep;
+        self
+    }
+
     fn peek(&self) -> Option<char> 
     {
         self.input.get(self.position).copied()
@@ -75,6 +298,19 @@ impl Tokenizer
     {
         let ch = self.peek();
         self.position += 1;
+        if let Some(c) = ch 
+        {
+            self.location.offset += c.len_utf8();
+            if c == '\n' 
+            {
+                self.location.line += 1;
+                self.location.column = 1;
+            } 
+            else 
+            {
+                self.location.column += 1;
+            }
+        }
         ch
     }
 
@@ -111,134 +347,341 @@ impl Tokenizer
         result
     }
 
-    fn lookup_keyword(word: &str) -> Option<Keyword> 
+    /// Reads up to the closing `quote`, the opening one already consumed.
+    /// A doubled quote stands for the quote character itself, so `'O''Brien'`
+    /// and `"say ""hi"""` are single tokens. `what` names the token for the
+    /// error if the quote is never closed.
+    fn read_quoted(&mut self, quote: char, what: &str) -> Result<String, ParseError> 
+    {
+        let mut result = String::new();
+        loop 
+        {
+            match self.advance() 
+            {
+                Some(ch) if ch == quote => 
+                {
+                    if self.peek() != Some(quote) 
+                    {
+                        return Ok(result);
+                    }
+                    self.advance();
+                    result.push(quote);
+                }
+                Some(ch) => result.push(ch),
+                None => return Err(self.unterminated(quote, what)),
+            }
+        }
+    }
+
+    /// Reads the body of a string closed by `quote` that also understands
+    /// C-style backslash escapes such as `\n` and `\'`, as in `E'...'`.
+    fn read_escaped_string(&mut self, quote: char) -> Result<String, ParseError> 
+    {
+        let mut result = String::new();
+        loop 
+        {
+            match self.advance() 
+            {
+                Some('\\') => match self.advance() 
+                {
+                    Some('n') => result.push('\n'),
+                    Some('t') => result.push('\t'),
+                    Some('r') => result.push('\r'),
+                    Some('b') => result.push('\u{8}'),
+                    Some('f') => result.push('\u{c}'),
+                    Some('0') => result.push('\0'),
+                    Some(ch) => result.push(ch),
+                    None => return Err(self.unterminated(quote, "string literal")),
+                },
+                Some(ch) if ch == quote => 
+                {
+                    if self.peek() != Some(quote) 
+                    {
+                        return Ok(result);
+                    }
+                    self.advance();
+                    result.push(quote);
+                }
+                Some(ch) => result.push(ch),
+                None => return Err(self.unterminated(quote, "string literal")),
+            }
+        }
+    }
+
+    fn unterminated(&self, quote: char, what: &str) -> ParseError 
+    {
+        self.invalid_token(&format!("Unterminated {}", what))
+            .with_help(format!("add a closing {} to end it", quote))
+    }
+
+    /// Checks that the dialect allows the operator spelled `text`.
+    fn operator(&self, text: &str, token: Token) -> Result<Token, ParseError> 
+    {
+        if self.dialect.supports_operator(text) 
+        {
+            Ok(token)
+        } 
+        else 
+        {
+            Err(self.invalid_token(&format!("Operator {} is not supported by the {} dialect", text, self.dialect.name())))
+        }
+    }
+
+    pub(crate) fn lookup_keyword(word: &str) -> Option<Keyword> 
+    {
+        KEYWORDS.iter().find(|(spelling, _)| spelling.eq_ignore_ascii_case(word)).map(|(_, keyword)| keyword.clone())
+    }
+
+    /// Every keyword, in no particular order.
+    pub(crate) fn keywords() -> impl Iterator<Item = Keyword> 
+    {
+        KEYWORDS.iter().map(|(_, keyword)| keyword.clone())
+    }
+
+    pub fn next_token(&mut self) -> Result<SpannedToken, ParseError> 
+    {
+        loop 
+        {
+            self.skip_whitespace();
+            self.token_start = self.location;
+            let token = self.read_token()?;
+            if matches!(token, Token::Comment(_)) && !self.keep_comments 
+            {
+                continue;
+            }
+            return Ok(SpannedToken { token, span: Span::new(self.token_start, self.location) });
+        }
+    }
+
+    /// An error covering the token read so far.
+    fn invalid_token(&self, message: &str) -> ParseError 
+    {
+        ParseError::new(ParseErrorKind::InvalidToken(message.to_string()), Span::new(self.token_start, self.location))
+    }
+
+    fn read_digits(&mut self, text: &mut String) 
+    {
+        while let Some(ch) = self.peek() 
+        {
+            if ch.is_ascii_digit() 
+            {
+                text.push(ch);
+                self.advance();
+            } 
+            else 
+            {
+                break;
+            }
+        }
+    }
+
+    /// Reads the rest of a numeric literal: `digits[.digits][e[+-]digits]`,
+    /// where the integer part may be left out as in `.5`.
+    fn read_number(&mut self, first: char) -> Result<Token, ParseError> 
+    {
+        let mut text = String::new();
+        text.push(first);
+        if first != '.' 
+        {
+            self.read_digits(&mut text);
+            if self.peek() == Some('.') 
+            {
+                text.push('.');
+                self.advance();
+            }
+        }
+        self.read_digits(&mut text);
+
+        if let Some(e @ ('e' | 'E')) = self.peek() 
+        {
+            text.push(e);
+            self.advance();
+            if let Some(sign @ ('+' | '-')) = self.peek() 
+            {
+                text.push(sign);
+                self.advance();
+            }
+            if !self.peek().is_some_and(|ch| ch.is_ascii_digit()) 
+            {
+                return Err(self.invalid_token("Missing digits in exponent of number literal")
+                    .with_found(text)
+                    .with_help("write the exponent as e.g. 1e10 or 2.5E-3"));
+            }
+            self.read_digits(&mut text);
+        }
+        Ok(Token::Number(text))
+    }
+
+    /// Reads the rest of a `--` comment up to, but not including, the newline.
+    fn read_line_comment(&mut self) -> Token 
+    {
+        let mut text = String::from("-");
+        while let Some(ch) = self.peek() 
+        {
+            if ch == '\n' 
+            {
+                break;
+            }
+            text.push(ch);
+            self.advance();
+        }
+        Token::Comment(text)
+    }
+
+    /// Reads the rest of a `/*` comment. Block comments nest, so
+    /// `/* a /* b */ c */` is a single comment.
+    fn read_block_comment(&mut self) -> Result<Token, ParseError> 
     {
-        match word.to_uppercase().as_str() 
+        self.advance(); // the '*' of the opening "/*"
+        let mut text = String::from("/*");
+        let mut depth = 1;
+        while let Some(ch) = self.advance() 
         {
-            "SELECT" => Some(Keyword::Select),
-            "FROM" => Some(Keyword::From),
-            "WHERE" => Some(Keyword::Where),
-            "ORDER" => Some(Keyword::Order),
-            "BY" => Some(Keyword::By),
-            "CREATE" => Some(Keyword::Create),
-            "TABLE" => Some(Keyword::Table),
-            "INT" => Some(Keyword::Int),
-            "BOOL" => Some(Keyword::Bool),
-            "VARCHAR" => Some(Keyword::Varchar),
-            "NOT" => Some(Keyword::Not),
-            "NULL" => Some(Keyword::Null),
-            "PRIMARY" => Some(Keyword::Primary),
-            "KEY" => Some(Keyword::Key),
-            "CHECK" => Some(Keyword::Check),
-            "AND" => Some(Keyword::And),
-            "OR" => Some(Keyword::Or),
-            "TRUE" => Some(Keyword::True),
-            "FALSE" => Some(Keyword::False),
-            _ => None,
+            text.push(ch);
+            match (ch, self.peek()) 
+            {
+                ('/', Some('*')) => 
+                {
+                    self.advance();
+                    text.push('*');
+                    depth += 1;
+                }
+                ('*', Some('/')) => 
+                {
+                    self.advance();
+                    text.push('/');
+                    depth -= 1;
+                    if depth == 0 
+                    {
+                        return Ok(Token::Comment(text));
+                    }
+                }
+                _ => {}
+            }
         }
+        Err(self.invalid_token("Unterminated block comment").with_help("add a closing */ to end it"))
     }
 
-    pub fn next_token(&mut self) -> Token 
+    /// Reads every token up to and including `Token::Eof`.
+    pub fn tokenize(&mut self) -> Result<Vec<SpannedToken>, ParseError> 
     {
-        self.skip_whitespace();
+        let mut tokens = Vec::new();
+        loop 
+        {
+            let token = self.next_token()?;
+            let done = token.token == Token::Eof;
+            tokens.push(token);
+            if done 
+            {
+                return Ok(tokens);
+            }
+        }
+    }
 
-        match self.advance() 
+    fn read_token(&mut self) -> Result<Token, ParseError> 
+    {
+        let token = match self.advance() 
         {
             Some(',') => Token::Comma,
             Some(';') => Token::Semicolon,
             Some('(') => Token::LeftParentheses,
             Some(')') => Token::RightParentheses,
-            Some('+') => Token::Plus,
-            Some('-') => Token::Minus,
-Some('*') => Token::Identifier("*".to_string()), // Treat * as a wildcard, not Multiply
-            Some('/') => Token::Divide,
-            Some('=') => Token::Equal,
+            Some('+') => self.operator("+", Token::Plus)?,
+            Some('-') if self.peek() == Some('-') => self.read_line_comment(),
+            Some('-') => self.operator("-", Token::Minus)?,
+            Some('*') => self.operator("*", Token::Multiply)?,
+            Some('.') if self.peek().is_some_and(|ch| ch.is_ascii_digit()) => self.read_number('.')?,
+            Some('.') => Token::Period,
+            Some('/') if self.peek() == Some('*') => self.read_block_comment()?,
+            Some('/') => self.operator("/", Token::Divide)?,
+            Some('=') => self.operator("=", Token::Equal)?,
             Some('>') => {
                 if self.peek() == Some('=') {
                     self.advance();
-                    Token::GreaterThanOrEqual
+                    self.operator(">=", Token::GreaterThanOrEqual)?
                 } 
                 else 
                 {
-                    Token::GreaterThan
+                    self.operator(">", Token::GreaterThan)?
                 }
             }
             Some('<') => {
                 if self.peek() == Some('=') 
                 {
                     self.advance();
-                    Token::LessThanOrEqual
+                    self.operator("<=", Token::LessThanOrEqual)?
+                } 
+                else if self.peek() == Some('>') 
+                {
+                    self.advance();
+                    self.operator("<>", Token::NotEqual)?
                 } 
                 else 
                 {
-                    Token::LessThan
+                    self.operator("<", Token::LessThan)?
                 }
             }
+            Some('?') => Token::Placeholder("?".to_string()),
+            Some('$') if self.peek().is_some_and(|ch| ch.is_ascii_digit()) => 
+            {
+                let mut text = String::from("$");
+                self.read_digits(&mut text);
+                Token::Placeholder(text)
+            }
+            Some(':') if self.peek().is_some_and(|ch| ch.is_alphabetic()) => 
+            {
+                Token::Placeholder(format!(":{}", self.read_identifier()))
+            }
             Some('!') => 
             {
                 if self.peek() == Some('=') 
                 {
                     self.advance();
-                    Token::NotEqual
+                    self.operator("!=", Token::NotEqual)?
                 } 
                 else 
                 {
                     Token::Invalid('!')
                 }
             }
+            Some('e' | 'E') if self.peek() == Some('\'') && self.dialect.supports_escape_string_literals() => 
+            {
+                self.advance();
+                Token::String(self.read_escaped_string('\'')?)
+            }
             Some(ch) if ch.is_alphabetic() => 
             {
                 let mut ident = String::new();
                 ident.push(ch);
                 ident.push_str(&self.read_identifier());
 
-                if let Some(keyword) = Self::lookup_keyword(&ident) 
-                {
-                    Token::Keyword(keyword)
-                } 
-                else 
+                match Self::lookup_keyword(&ident) 
                 {
-                    Token::Identifier(ident)
+                    Some(keyword) if !keyword.is_contextual() && self.dialect.is_reserved_keyword(&keyword) => Token::Keyword(keyword),
+                    _ => Token::Identifier(ident),
                 }
             }
-            Some(ch) if ch.is_digit(10) => 
+            Some(ch) if ch.is_ascii_digit() => self.read_number(ch)?,
+            Some(ch) if self.dialect.is_string_quote(ch) => 
             {
-                let mut num_str = String::new();
-                num_str.push(ch);
-                while let Some(next) = self.peek() 
+                if self.dialect.supports_backslash_escapes() 
                 {
-                    if next.is_digit(10) {
-                        num_str.push(next);
-                        self.advance();
-                    } 
-                    else 
-                    {
-                        break;
-                    }
+                    Token::String(self.read_escaped_string(ch)?)
+                } 
+                else 
+                {
+                    Token::String(self.read_quoted(ch, "string literal")?)
                 }
-                Token::Number(num_str.parse::<u64>().unwrap())
             }
-            Some('"') | Some('\'') => 
+            Some(ch) if self.dialect.identifier_quote_end(ch).is_some() => 
             {
-                let quote = self.input[self.position - 1];
-                let mut result = String::new();
-                while let Some(ch) = self.peek() 
-                {
-                    self.advance();
-                    if ch == quote 
-                    {
-                        return Token::String(result);
-                    } 
-                    else 
-                    {
-                        result.push(ch);
-                    }
-                }
-                Token::Invalid(quote)
+                let close = self.dialect.identifier_quote_end(ch).unwrap_or(ch);
+                Token::QuotedIdentifier(self.read_quoted(close, "quoted identifier")?)
             }
             None => Token::Eof,
             Some(ch) => Token::Invalid(ch),
-        }
+        };
+        Ok(token)
     }
 }
 #[cfg(test)]
@@ -248,19 +691,119 @@ mod tests {
     #[test]
     fn test_wildcard_token() {
         let mut tokenizer = Tokenizer::new("*");
-        assert_eq!(tokenizer.next_token(), Token::Identifier("*".to_string()));
+        assert_eq!(tokenizer.next_token().unwrap().token, Token::Multiply);
     }
 
     #[test]
     fn test_keyword_and_identifier() {
         let mut tokenizer = Tokenizer::new("SELECT age");
-        assert_eq!(tokenizer.next_token(), Token::Keyword(Keyword::Select));
-        assert_eq!(tokenizer.next_token(), Token::Identifier("age".to_string()));
+        assert_eq!(tokenizer.next_token().unwrap().token, Token::Keyword(Keyword::Select));
+        assert_eq!(tokenizer.next_token().unwrap().token, Token::Identifier("age".to_string()));
     }
 
     #[test]
     fn test_number_token() {
         let mut tokenizer = Tokenizer::new("123");
-        assert_eq!(tokenizer.next_token(), Token::Number(123));
+        assert_eq!(tokenizer.next_token().unwrap().token, Token::Number("123".to_string()));
+    }
+
+    #[test]
+    fn test_decimal_and_scientific_numbers() {
+        let tokens = Tokenizer::new("3.14 .5 1. 1e-9 2E+10 99999999999999999999 t.x").tokenize().unwrap();
+        let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+        let number = |text: &str| Token::Number(text.to_string());
+        assert_eq!(tokens, vec![
+            number("3.14"),
+            number(".5"),
+            number("1."),
+            number("1e-9"),
+            number("2E+10"),
+            number("99999999999999999999"),
+            Token::Identifier("t".to_string()),
+            Token::Period,
+            Token::Identifier("x".to_string()),
+            Token::Eof,
+        ]);
+    }
+
+    #[test]
+    fn test_malformed_exponent_is_an_error() {
+        let err = Tokenizer::new("SELECT 1e+ FROM t").tokenize().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidToken("Missing digits in exponent of number literal".to_string()));
+        assert_eq!((err.span.start.column, err.span.end.column), (8, 11));
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let mut tokenizer = Tokenizer::new("SELECT a,\n  bé FROM t");
+        let tokens = tokenizer.tokenize().unwrap();
+        let select = tokens[0].span;
+        assert_eq!((select.start.offset, select.end.offset), (0, 6));
+        assert_eq!((select.start.line, select.start.column), (1, 1));
+        let be = tokens[3].span;
+        assert_eq!(tokens[3].token, Token::Identifier("bé".to_string()));
+        assert_eq!((be.start.line, be.start.column), (2, 3));
+        assert_eq!((be.start.offset, be.end.offset), (12, 15));
+        assert_eq!((be.end.line, be.end.column), (2, 5));
+        assert_eq!(tokens.last().unwrap().token, Token::Eof);
+
+        // The same token somewhere else is a different token.
+        let moved = Tokenizer::new(" SELECT").tokenize().unwrap();
+        assert_eq!(moved[0].token, tokens[0].token);
+        assert_ne!(moved[0], tokens[0]);
+    }
+
+    #[test]
+    fn test_comments_are_skipped() {
+        let tokens = Tokenizer::new("-- header\nSELECT /* a /* nested */ b */ a--trailing\n- 1 / 2").tokenize().unwrap();
+        let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+        assert_eq!(tokens, vec![
+            Token::Keyword(Keyword::Select),
+            Token::Identifier("a".to_string()),
+            Token::Minus,
+            Token::Number("1".to_string()),
+            Token::Divide,
+            Token::Number("2".to_string()),
+            Token::Eof,
+        ]);
+    }
+
+    #[test]
+    fn test_comments_kept_as_trivia() {
+        let tokens = Tokenizer::new("/* x */ a -- y").keep_comments(true).tokenize().unwrap();
+        assert_eq!(tokens[0].token, Token::Comment("/* x */".to_string()));
+        assert_eq!((tokens[0].span.start.offset, tokens[0].span.end.offset), (0, 7));
+        assert_eq!(tokens[1].token, Token::Identifier("a".to_string()));
+        assert_eq!(tokens[2].token, Token::Comment("-- y".to_string()));
+        assert_eq!(tokens[3].token, Token::Eof);
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let err = Tokenizer::new("a /* never closed").tokenize().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidToken("Unterminated block comment".to_string()));
+        assert_eq!(err.span.start.column, 3);
+    }
+
+    #[test]
+    fn test_string_literals_and_quoted_identifiers() {
+        let tokens = Tokenizer::new(r#"'O''Brien' E'tab\there\'s' "Mixed Case" "say ""hi""" e"#).tokenize().unwrap();
+        let tokens: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+        assert_eq!(tokens, vec![
+            Token::String("O'Brien".to_string()),
+            Token::String("tab\there's".to_string()),
+            Token::QuotedIdentifier("Mixed Case".to_string()),
+            Token::QuotedIdentifier("say \"hi\"".to_string()),
+            Token::Identifier("e".to_string()),
+            Token::Eof,
+        ]);
+    }
+
+    #[test]
+    fn test_unterminated_string_reports_start() {
+        let err = Tokenizer::new("SELECT a FROM t WHERE name = 'oops").tokenize().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidToken("Unterminated string literal".to_string()));
+        assert_eq!((err.span.start.line, err.span.start.column), (1, 30));
+        assert_eq!(err.help.as_deref(), Some("add a closing ' to end it"));
     }
 }
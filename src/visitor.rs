@@ -0,0 +1,799 @@
+//Created by: Nisha Murali (Requested not to clone / re-use the code)
+//visitor.rs (Walks the AST so tools don't have to hand-write the recursion)
+use crate::ast::{
+    Statement, Expression, ExpressionKind, FunctionArg, SelectItem, TableReference, JoinConstraint,
+    InsertSource, TableColumn, Constraint, Query, SetExpr, Select, With, Cte, OrderByItem, Assignment,
+    CaseBranch,
+};
+
+/// Read-only traversal of the AST.
+///
+/// `walk_statement` visits every node depth-first, starting with any WITH
+/// clause. For each node type the `pre_visit_*` hook runs before its
+/// children and `post_visit_*` after them; override only the hooks you
+/// need. `visit_relation` is called for every table name a statement
+/// refers to.
+pub trait Visitor
+{
+    fn pre_visit_statement(&mut self, _statement: &Statement) {}
+    fn post_visit_statement(&mut self, _statement: &Statement) {}
+    fn pre_visit_query(&mut self, _query: &Query) {}
+    fn post_visit_query(&mut self, _query: &Query) {}
+    fn pre_visit_with(&mut self, _with: &With) {}
+    fn post_visit_with(&mut self, _with: &With) {}
+    fn pre_visit_cte(&mut self, _cte: &Cte) {}
+    fn post_visit_cte(&mut self, _cte: &Cte) {}
+    fn pre_visit_set_expr(&mut self, _body: &SetExpr) {}
+    fn post_visit_set_expr(&mut self, _body: &SetExpr) {}
+    fn pre_visit_select(&mut self, _select: &Select) {}
+    fn post_visit_select(&mut self, _select: &Select) {}
+    fn pre_visit_order_by_item(&mut self, _item: &OrderByItem) {}
+    fn post_visit_order_by_item(&mut self, _item: &OrderByItem) {}
+    fn pre_visit_expression(&mut self, _expr: &Expression) {}
+    fn post_visit_expression(&mut self, _expr: &Expression) {}
+    fn pre_visit_case_branch(&mut self, _branch: &CaseBranch) {}
+    fn post_visit_case_branch(&mut self, _branch: &CaseBranch) {}
+    fn pre_visit_select_item(&mut self, _item: &SelectItem) {}
+    fn post_visit_select_item(&mut self, _item: &SelectItem) {}
+    fn pre_visit_table_reference(&mut self, _table: &TableReference) {}
+    fn post_visit_table_reference(&mut self, _table: &TableReference) {}
+    fn pre_visit_table_column(&mut self, _column: &TableColumn) {}
+    fn post_visit_table_column(&mut self, _column: &TableColumn) {}
+    fn pre_visit_constraint(&mut self, _constraint: &Constraint) {}
+    fn post_visit_constraint(&mut self, _constraint: &Constraint) {}
+    fn pre_visit_assignment(&mut self, _assignment: &Assignment) {}
+    fn post_visit_assignment(&mut self, _assignment: &Assignment) {}
+    fn visit_relation(&mut self, _name: &str) {}
+}
+
+/// Mutable counterpart of [`Visitor`]. Hooks may rewrite the node they are
+/// given; a `pre_visit_*` rewrite is walked, a `post_visit_*` one is not.
+pub trait VisitorMut
+{
+    fn pre_visit_statement(&mut self, _statement: &mut Statement) {}
+    fn post_visit_statement(&mut self, _statement: &mut Statement) {}
+    fn pre_visit_query(&mut self, _query: &mut Query) {}
+    fn post_visit_query(&mut self, _query: &mut Query) {}
+    fn pre_visit_with(&mut self, _with: &mut With) {}
+    fn post_visit_with(&mut self, _with: &mut With) {}
+    fn pre_visit_cte(&mut self, _cte: &mut Cte) {}
+    fn post_visit_cte(&mut self, _cte: &mut Cte) {}
+    fn pre_visit_set_expr(&mut self, _body: &mut SetExpr) {}
+    fn post_visit_set_expr(&mut self, _body: &mut SetExpr) {}
+    fn pre_visit_select(&mut self, _select: &mut Select) {}
+    fn post_visit_select(&mut self, _select: &mut Select) {}
+    fn pre_visit_order_by_item(&mut self, _item: &mut OrderByItem) {}
+    fn post_visit_order_by_item(&mut self, _item: &mut OrderByItem) {}
+    fn pre_visit_expression(&mut self, _expr: &mut Expression) {}
+    fn post_visit_expression(&mut self, _expr: &mut Expression) {}
+    fn pre_visit_case_branch(&mut self, _branch: &mut CaseBranch) {}
+    fn post_visit_case_branch(&mut self, _branch: &mut CaseBranch) {}
+    fn pre_visit_select_item(&mut self, _item: &mut SelectItem) {}
+    fn post_visit_select_item(&mut self, _item: &mut SelectItem) {}
+    fn pre_visit_table_reference(&mut self, _table: &mut TableReference) {}
+    fn post_visit_table_reference(&mut self, _table: &mut TableReference) {}
+    fn pre_visit_table_column(&mut self, _column: &mut TableColumn) {}
+    fn post_visit_table_column(&mut self, _column: &mut TableColumn) {}
+    fn pre_visit_constraint(&mut self, _constraint: &mut Constraint) {}
+    fn post_visit_constraint(&mut self, _constraint: &mut Constraint) {}
+    fn pre_visit_assignment(&mut self, _assignment: &mut Assignment) {}
+    fn post_visit_assignment(&mut self, _assignment: &mut Assignment) {}
+    fn visit_relation(&mut self, _name: &mut String) {}
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement)
+{
+    visitor.pre_visit_statement(statement);
+    if let Statement::Insert { with: Some(with), .. }
+        | Statement::Update { with: Some(with), .. }
+        | Statement::Delete { with: Some(with), .. } = statement
+    {
+        walk_with(visitor, with);
+    }
+    match statement
+    {
+        Statement::Query(query) => walk_query(visitor, query),
+        Statement::CreateTable { table_name, columns, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            for column in columns
+            {
+                walk_table_column(visitor, column);
+            }
+        }
+        Statement::Insert { table_name, source, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            match source
+            {
+                InsertSource::Values(rows) =>
+                {
+                    for expr in rows.iter().flatten()
+                    {
+                        walk_expression(visitor, expr);
+                    }
+                }
+                InsertSource::Query(query) => walk_query(visitor, query),
+            }
+        }
+        Statement::Update { table_name, assignments, selection, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            for assignment in assignments
+            {
+                walk_assignment(visitor, assignment);
+            }
+            if let Some(selection) = selection
+            {
+                walk_expression(visitor, selection);
+            }
+        }
+        Statement::Delete { table_name, selection, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            if let Some(selection) = selection
+            {
+                walk_expression(visitor, selection);
+            }
+        }
+    }
+    visitor.post_visit_statement(statement);
+}
+
+pub fn walk_with<V: Visitor + ?Sized>(visitor: &mut V, with: &With)
+{
+    visitor.pre_visit_with(with);
+    for cte in &with.ctes
+    {
+        walk_cte(visitor, cte);
+    }
+    visitor.post_visit_with(with);
+}
+
+pub fn walk_cte<V: Visitor + ?Sized>(visitor: &mut V, cte: &Cte)
+{
+    visitor.pre_visit_cte(cte);
+    walk_query(visitor, &cte.query);
+    visitor.post_visit_cte(cte);
+}
+
+pub fn walk_query<V: Visitor + ?Sized>(visitor: &mut V, query: &Query)
+{
+    visitor.pre_visit_query(query);
+    if let Some(with) = &query.with
+    {
+        walk_with(visitor, with);
+    }
+    walk_set_expr(visitor, &query.body);
+    for item in &query.order_by
+    {
+        walk_order_by_item(visitor, item);
+    }
+    for expr in [&query.limit, &query.offset, &query.fetch].into_iter().flatten()
+    {
+        walk_expression(visitor, expr);
+    }
+    visitor.post_visit_query(query);
+}
+
+pub fn walk_set_expr<V: Visitor + ?Sized>(visitor: &mut V, body: &SetExpr)
+{
+    visitor.pre_visit_set_expr(body);
+    match body
+    {
+        SetExpr::Select(select) => walk_select(visitor, select),
+        SetExpr::Query(query) => walk_query(visitor, query),
+        SetExpr::SetOperation { left, right, .. } =>
+        {
+            walk_set_expr(visitor, left);
+            walk_set_expr(visitor, right);
+        }
+    }
+    visitor.post_visit_set_expr(body);
+}
+
+pub fn walk_select<V: Visitor + ?Sized>(visitor: &mut V, select: &Select)
+{
+    visitor.pre_visit_select(select);
+    if let Some(top) = &select.top
+    {
+        walk_expression(visitor, top);
+    }
+    for item in &select.columns
+    {
+        walk_select_item(visitor, item);
+    }
+    for table in &select.from
+    {
+        walk_table_reference(visitor, table);
+    }
+    if let Some(selection) = &select.selection
+    {
+        walk_expression(visitor, selection);
+    }
+    for expr in &select.group_by
+    {
+        walk_expression(visitor, expr);
+    }
+    if let Some(having) = &select.having
+    {
+        walk_expression(visitor, having);
+    }
+    visitor.post_visit_select(select);
+}
+
+pub fn walk_order_by_item<V: Visitor + ?Sized>(visitor: &mut V, item: &OrderByItem)
+{
+    visitor.pre_visit_order_by_item(item);
+    walk_expression(visitor, &item.expr);
+    visitor.post_visit_order_by_item(item);
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expression)
+{
+    visitor.pre_visit_expression(expr);
+    match &expr.kind
+    {
+        ExpressionKind::UnaryOperation { operand, .. } => walk_expression(visitor, operand),
+        ExpressionKind::BinaryOperation { left_operand, right_operand, .. } =>
+        {
+            walk_expression(visitor, left_operand);
+            walk_expression(visitor, right_operand);
+        }
+        ExpressionKind::Grouped(inner) => walk_expression(visitor, inner),
+        ExpressionKind::Subquery(subquery) | ExpressionKind::Exists(subquery) => walk_query(visitor, subquery),
+        ExpressionKind::InList { expr, list, .. } =>
+        {
+            walk_expression(visitor, expr);
+            for item in list
+            {
+                walk_expression(visitor, item);
+            }
+        }
+        ExpressionKind::InSubquery { expr, subquery, .. } =>
+        {
+            walk_expression(visitor, expr);
+            walk_query(visitor, subquery);
+        }
+        ExpressionKind::QuantifiedComparison { left_operand, subquery, .. } =>
+        {
+            walk_expression(visitor, left_operand);
+            walk_query(visitor, subquery);
+        }
+        ExpressionKind::IsNull { expr, .. } => walk_expression(visitor, expr),
+        ExpressionKind::Case { operand, branches, else_result } =>
+        {
+            if let Some(operand) = operand
+            {
+                walk_expression(visitor, operand);
+            }
+            for branch in branches
+            {
+                walk_case_branch(visitor, branch);
+            }
+            if let Some(else_result) = else_result
+            {
+                walk_expression(visitor, else_result);
+            }
+        }
+        ExpressionKind::Function { args, filter, .. } =>
+        {
+            for arg in args
+            {
+                if let FunctionArg::Expression(arg) = arg
+                {
+                    walk_expression(visitor, arg);
+                }
+            }
+            if let Some(filter) = filter
+            {
+                walk_expression(visitor, filter);
+            }
+        }
+        ExpressionKind::Identifier(_) | ExpressionKind::CompoundIdentifier(_) | ExpressionKind::Number(_) |
+        ExpressionKind::String(_) | ExpressionKind::Boolean(_) | ExpressionKind::Null | ExpressionKind::Placeholder(_) => {}
+    }
+    visitor.post_visit_expression(expr);
+}
+
+pub fn walk_case_branch<V: Visitor + ?Sized>(visitor: &mut V, branch: &CaseBranch)
+{
+    visitor.pre_visit_case_branch(branch);
+    walk_expression(visitor, &branch.condition);
+    walk_expression(visitor, &branch.result);
+    visitor.post_visit_case_branch(branch);
+}
+
+pub fn walk_select_item<V: Visitor + ?Sized>(visitor: &mut V, item: &SelectItem)
+{
+    visitor.pre_visit_select_item(item);
+    if let SelectItem::Expression { expr, .. } = item
+    {
+        walk_expression(visitor, expr);
+    }
+    visitor.post_visit_select_item(item);
+}
+
+pub fn walk_table_reference<V: Visitor + ?Sized>(visitor: &mut V, table: &TableReference)
+{
+    visitor.pre_visit_table_reference(table);
+    match table
+    {
+        TableReference::Table { name, .. } => visitor.visit_relation(name),
+        TableReference::Derived { subquery, .. } => walk_query(visitor, subquery),
+        TableReference::Join { left, right, constraint, .. } =>
+        {
+            walk_table_reference(visitor, left);
+            walk_table_reference(visitor, right);
+            if let JoinConstraint::On(expr) = constraint
+            {
+                walk_expression(visitor, expr);
+            }
+        }
+    }
+    visitor.post_visit_table_reference(table);
+}
+
+pub fn walk_table_column<V: Visitor + ?Sized>(visitor: &mut V, column: &TableColumn)
+{
+    visitor.pre_visit_table_column(column);
+    for constraint in &column.constraints
+    {
+        walk_constraint(visitor, constraint);
+    }
+    visitor.post_visit_table_column(column);
+}
+
+pub fn walk_constraint<V: Visitor + ?Sized>(visitor: &mut V, constraint: &Constraint)
+{
+    visitor.pre_visit_constraint(constraint);
+    if let Constraint::Check(expr) = constraint
+    {
+        walk_expression(visitor, expr);
+    }
+    visitor.post_visit_constraint(constraint);
+}
+
+pub fn walk_assignment<V: Visitor + ?Sized>(visitor: &mut V, assignment: &Assignment)
+{
+    visitor.pre_visit_assignment(assignment);
+    walk_expression(visitor, &assignment.value);
+    visitor.post_visit_assignment(assignment);
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement)
+{
+    visitor.pre_visit_statement(statement);
+    if let Statement::Insert { with: Some(with), .. }
+        | Statement::Update { with: Some(with), .. }
+        | Statement::Delete { with: Some(with), .. } = statement
+    {
+        walk_with_mut(visitor, with);
+    }
+    match statement
+    {
+        Statement::Query(query) => walk_query_mut(visitor, query),
+        Statement::CreateTable { table_name, columns, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            for column in columns
+            {
+                walk_table_column_mut(visitor, column);
+            }
+        }
+        Statement::Insert { table_name, source, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            match source
+            {
+                InsertSource::Values(rows) =>
+                {
+                    for expr in rows.iter_mut().flatten()
+                    {
+                        walk_expression_mut(visitor, expr);
+                    }
+                }
+                InsertSource::Query(query) => walk_query_mut(visitor, query),
+            }
+        }
+        Statement::Update { table_name, assignments, selection, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            for assignment in assignments
+            {
+                walk_assignment_mut(visitor, assignment);
+            }
+            if let Some(selection) = selection
+            {
+                walk_expression_mut(visitor, selection);
+            }
+        }
+        Statement::Delete { table_name, selection, .. } =>
+        {
+            visitor.visit_relation(table_name);
+            if let Some(selection) = selection
+            {
+                walk_expression_mut(visitor, selection);
+            }
+        }
+    }
+    visitor.post_visit_statement(statement);
+}
+
+pub fn walk_with_mut<V: VisitorMut + ?Sized>(visitor: &mut V, with: &mut With)
+{
+    visitor.pre_visit_with(with);
+    for cte in &mut with.ctes
+    {
+        walk_cte_mut(visitor, cte);
+    }
+    visitor.post_visit_with(with);
+}
+
+pub fn walk_cte_mut<V: VisitorMut + ?Sized>(visitor: &mut V, cte: &mut Cte)
+{
+    visitor.pre_visit_cte(cte);
+    walk_query_mut(visitor, &mut cte.query);
+    visitor.post_visit_cte(cte);
+}
+
+pub fn walk_query_mut<V: VisitorMut + ?Sized>(visitor: &mut V, query: &mut Query)
+{
+    visitor.pre_visit_query(query);
+    if let Some(with) = &mut query.with
+    {
+        walk_with_mut(visitor, with);
+    }
+    walk_set_expr_mut(visitor, &mut query.body);
+    for item in &mut query.order_by
+    {
+        walk_order_by_item_mut(visitor, item);
+    }
+    for expr in [&mut query.limit, &mut query.offset, &mut query.fetch].into_iter().flatten()
+    {
+        walk_expression_mut(visitor, expr);
+    }
+    visitor.post_visit_query(query);
+}
+
+pub fn walk_set_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, body: &mut SetExpr)
+{
+    visitor.pre_visit_set_expr(body);
+    match body
+    {
+        SetExpr::Select(select) => walk_select_mut(visitor, select),
+        SetExpr::Query(query) => walk_query_mut(visitor, query),
+        SetExpr::SetOperation { left, right, .. } =>
+        {
+            walk_set_expr_mut(visitor, left);
+            walk_set_expr_mut(visitor, right);
+        }
+    }
+    visitor.post_visit_set_expr(body);
+}
+
+pub fn walk_select_mut<V: VisitorMut + ?Sized>(visitor: &mut V, select: &mut Select)
+{
+    visitor.pre_visit_select(select);
+    if let Some(top) = &mut select.top
+    {
+        walk_expression_mut(visitor, top);
+    }
+    for item in &mut select.columns
+    {
+        walk_select_item_mut(visitor, item);
+    }
+    for table in &mut select.from
+    {
+        walk_table_reference_mut(visitor, table);
+    }
+    if let Some(selection) = &mut select.selection
+    {
+        walk_expression_mut(visitor, selection);
+    }
+    for expr in &mut select.group_by
+    {
+        walk_expression_mut(visitor, expr);
+    }
+    if let Some(having) = &mut select.having
+    {
+        walk_expression_mut(visitor, having);
+    }
+    visitor.post_visit_select(select);
+}
+
+pub fn walk_order_by_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut OrderByItem)
+{
+    visitor.pre_visit_order_by_item(item);
+    walk_expression_mut(visitor, &mut item.expr);
+    visitor.post_visit_order_by_item(item);
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expression)
+{
+    visitor.pre_visit_expression(expr);
+    match &mut expr.kind
+    {
+        ExpressionKind::UnaryOperation { operand, .. } => walk_expression_mut(visitor, operand),
+        ExpressionKind::BinaryOperation { left_operand, right_operand, .. } =>
+        {
+            walk_expression_mut(visitor, left_operand);
+            walk_expression_mut(visitor, right_operand);
+        }
+        ExpressionKind::Grouped(inner) => walk_expression_mut(visitor, inner),
+        ExpressionKind::Subquery(subquery) | ExpressionKind::Exists(subquery) => walk_query_mut(visitor, subquery),
+        ExpressionKind::InList { expr, list, .. } =>
+        {
+            walk_expression_mut(visitor, expr);
+            for item in list
+            {
+                walk_expression_mut(visitor, item);
+            }
+        }
+        ExpressionKind::InSubquery { expr, subquery, .. } =>
+        {
+            walk_expression_mut(visitor, expr);
+            walk_query_mut(visitor, subquery);
+        }
+        ExpressionKind::QuantifiedComparison { left_operand, subquery, .. } =>
+        {
+            walk_expression_mut(visitor, left_operand);
+            walk_query_mut(visitor, subquery);
+        }
+        ExpressionKind::IsNull { expr, .. } => walk_expression_mut(visitor, expr),
+        ExpressionKind::Case { operand, branches, else_result } =>
+        {
+            if let Some(operand) = operand
+            {
+                walk_expression_mut(visitor, operand);
+            }
+            for branch in branches
+            {
+                walk_case_branch_mut(visitor, branch);
+            }
+            if let Some(else_result) = else_result
+            {
+                walk_expression_mut(visitor, else_result);
+            }
+        }
+        ExpressionKind::Function { args, filter, .. } =>
+        {
+            for arg in args
+            {
+                if let FunctionArg::Expression(arg) = arg
+                {
+                    walk_expression_mut(visitor, arg);
+                }
+            }
+            if let Some(filter) = filter
+            {
+                walk_expression_mut(visitor, filter);
+            }
+        }
+        ExpressionKind::Identifier(_) | ExpressionKind::CompoundIdentifier(_) | ExpressionKind::Number(_) |
+        ExpressionKind::String(_) | ExpressionKind::Boolean(_) | ExpressionKind::Null | ExpressionKind::Placeholder(_) => {}
+    }
+    visitor.post_visit_expression(expr);
+}
+
+pub fn walk_case_branch_mut<V: VisitorMut + ?Sized>(visitor: &mut V, branch: &mut CaseBranch)
+{
+    visitor.pre_visit_case_branch(branch);
+    walk_expression_mut(visitor, &mut branch.condition);
+    walk_expression_mut(visitor, &mut branch.result);
+    visitor.post_visit_case_branch(branch);
+}
+
+pub fn walk_select_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut SelectItem)
+{
+    visitor.pre_visit_select_item(item);
+    if let SelectItem::Expression { expr, .. } = item
+    {
+        walk_expression_mut(visitor, expr);
+    }
+    visitor.post_visit_select_item(item);
+}
+
+pub fn walk_table_reference_mut<V: VisitorMut + ?Sized>(visitor: &mut V, table: &mut TableReference)
+{
+    visitor.pre_visit_table_reference(table);
+    match table
+    {
+        TableReference::Table { name, .. } => visitor.visit_relation(name),
+        TableReference::Derived { subquery, .. } => walk_query_mut(visitor, subquery),
+        TableReference::Join { left, right, constraint, .. } =>
+        {
+            walk_table_reference_mut(visitor, left);
+            walk_table_reference_mut(visitor, right);
+            if let JoinConstraint::On(expr) = constraint
+            {
+                walk_expression_mut(visitor, expr);
+            }
+        }
+    }
+    visitor.post_visit_table_reference(table);
+}
+
+pub fn walk_table_column_mut<V: VisitorMut + ?Sized>(visitor: &mut V, column: &mut TableColumn)
+{
+    visitor.pre_visit_table_column(column);
+    for constraint in &mut column.constraints
+    {
+        walk_constraint_mut(visitor, constraint);
+    }
+    visitor.post_visit_table_column(column);
+}
+
+pub fn walk_constraint_mut<V: VisitorMut + ?Sized>(visitor: &mut V, constraint: &mut Constraint)
+{
+    visitor.pre_visit_constraint(constraint);
+    if let Constraint::Check(expr) = constraint
+    {
+        walk_expression_mut(visitor, expr);
+    }
+    visitor.post_visit_constraint(constraint);
+}
+
+pub fn walk_assignment_mut<V: VisitorMut + ?Sized>(visitor: &mut V, assignment: &mut Assignment)
+{
+    visitor.pre_visit_assignment(assignment);
+    walk_expression_mut(visitor, &mut assignment.value);
+    visitor.post_visit_assignment(assignment);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::parse_statement;
+
+    #[derive(Default)]
+    struct Collector {
+        tables: Vec<String>,
+        pre: Vec<String>,
+        post: Vec<String>,
+        queries: usize,
+        nodes: Vec<String>,
+    }
+
+    impl Visitor for Collector {
+        fn pre_visit_expression(&mut self, expr: &Expression) {
+            self.pre.push(expr.to_string());
+        }
+
+        fn post_visit_expression(&mut self, expr: &Expression) {
+            self.post.push(expr.to_string());
+        }
+
+        fn visit_relation(&mut self, name: &str) {
+            self.tables.push(name.to_string());
+        }
+
+        fn pre_visit_query(&mut self, _query: &Query) {
+            self.queries += 1;
+        }
+
+        fn pre_visit_with(&mut self, _with: &With) {
+            self.nodes.push("with".to_string());
+        }
+
+        fn post_visit_with(&mut self, _with: &With) {
+            self.nodes.push("/with".to_string());
+        }
+
+        fn pre_visit_cte(&mut self, cte: &Cte) {
+            self.nodes.push(format!("cte {}", cte.name));
+        }
+
+        fn pre_visit_set_expr(&mut self, body: &SetExpr) {
+            if let SetExpr::SetOperation { .. } = body {
+                self.nodes.push("set operation".to_string());
+            }
+        }
+
+        fn pre_visit_select(&mut self, _select: &Select) {
+            self.nodes.push("select".to_string());
+        }
+
+        fn post_visit_select(&mut self, _select: &Select) {
+            self.nodes.push("/select".to_string());
+        }
+
+        fn pre_visit_order_by_item(&mut self, item: &OrderByItem) {
+            self.nodes.push(format!("order by {}", item.expr));
+        }
+
+        fn pre_visit_case_branch(&mut self, branch: &CaseBranch) {
+            self.nodes.push(format!("when {}", branch.condition));
+        }
+
+        fn pre_visit_assignment(&mut self, assignment: &Assignment) {
+            self.nodes.push(format!("set {}", assignment.column));
+        }
+    }
+
+    #[test]
+    fn test_visitor_orders() {
+        let stmt = parse_statement("SELECT a FROM t JOIN u ON t.id = u.id WHERE b > 1").unwrap();
+        let mut collector = Collector::default();
+        walk_statement(&mut collector, &stmt);
+
+        assert_eq!(collector.tables, vec!["t", "u"]);
+        assert_eq!(collector.pre, vec!["a", "t.id = u.id", "t.id", "u.id", "b > 1", "b", "1"]);
+        assert_eq!(collector.post, vec!["a", "t.id", "u.id", "t.id = u.id", "b", "1", "b > 1"]);
+    }
+
+    #[test]
+    fn test_visitor_reaches_check_constraints() {
+        let stmt = parse_statement("CREATE TABLE t (age INT CHECK (age >= 18))").unwrap();
+        let mut collector = Collector::default();
+        walk_statement(&mut collector, &stmt);
+
+        assert_eq!(collector.tables, vec!["t"]);
+        assert_eq!(collector.post.last().map(String::as_str), Some("age >= 18"));
+    }
+
+    #[test]
+    fn test_visitor_enters_subqueries() {
+        let stmt = parse_statement("SELECT * FROM (SELECT id FROM a) AS x WHERE id IN (SELECT id FROM b) AND EXISTS (SELECT 1 FROM c)").unwrap();
+        let mut collector = Collector::default();
+        walk_statement(&mut collector, &stmt);
+        assert_eq!(collector.tables, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_visitor_enters_ctes() {
+        let stmt = parse_statement("WITH x AS (SELECT id FROM a), y AS (SELECT id FROM x) INSERT INTO b SELECT id FROM y").unwrap();
+        let mut collector = Collector::default();
+        walk_statement(&mut collector, &stmt);
+        assert_eq!(collector.tables, vec!["a", "x", "b", "y"]);
+    }
+
+    #[test]
+    fn test_visitor_walks_set_operations() {
+        let stmt = parse_statement("SELECT a FROM t UNION (SELECT a FROM u INTERSECT SELECT a FROM v WHERE a IN (SELECT a FROM w)) ORDER BY a").unwrap();
+        let mut collector = Collector::default();
+        walk_statement(&mut collector, &stmt);
+        assert_eq!(collector.tables, vec!["t", "u", "v", "w"]);
+        assert_eq!(collector.queries, 3);
+        assert_eq!(collector.post.last().map(String::as_str), Some("a"));
+    }
+
+    #[test]
+    fn test_visitor_hooks_every_node_type() {
+        let stmt = parse_statement("WITH x AS (SELECT a FROM t) SELECT CASE WHEN a = 1 THEN 2 END FROM x UNION SELECT b FROM u ORDER BY 1").unwrap();
+        let mut collector = Collector::default();
+        walk_statement(&mut collector, &stmt);
+        assert_eq!(collector.nodes, vec![
+            "with", "cte x", "select", "/select", "/with", "set operation",
+            "select", "when a = 1", "/select", "select", "/select", "order by 1",
+        ]);
+
+        let stmt = parse_statement("UPDATE t SET a = 1, b = 2").unwrap();
+        let mut collector = Collector::default();
+        walk_statement(&mut collector, &stmt);
+        assert_eq!(collector.nodes, vec!["set a", "set b"]);
+    }
+
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn pre_visit_expression(&mut self, expr: &mut Expression) {
+            if let ExpressionKind::Identifier(name) = &mut expr.kind {
+                if name == "old" {
+                    *name = "new".to_string();
+                }
+            }
+        }
+
+        fn visit_relation(&mut self, name: &mut String) {
+            name.insert_str(0, "archive_");
+        }
+    }
+
+    #[test]
+    fn test_visitor_mut_rewrites() {
+        let mut stmt = parse_statement("UPDATE t SET a = old + 1 WHERE old > 2").unwrap();
+        walk_statement_mut(&mut Rename, &mut stmt);
+        assert_eq!(stmt.to_string(), "UPDATE archive_t SET a = new + 1 WHERE new > 2");
+    }
+}